@@ -4,7 +4,10 @@ mod move_info;
 use tak::*;
 
 use self::{branch::Branch, move_info::MoveInfo};
-use crate::search::node::Node;
+use crate::{
+    config::{TINUE_DEPTH, TINUE_NODES},
+    search::node::Node,
+};
 
 const MAX_BRANCH_LENGTH: usize = 10;
 const BRANCH_MIN_VISITS: u32 = 100;
@@ -12,18 +15,21 @@ const CANDIDATE_MOVE_RATIO: f32 = 0.7;
 
 #[derive(Default)]
 pub struct Analysis<const N: usize> {
-    komi: i32,
-    played_turns: Vec<Turn<N>>,
+    record: GameRecord<N>,
     move_info: Vec<Option<MoveInfo>>,
     branches: Vec<Branch<N>>,
 }
 
 impl<const N: usize> Analysis<N> {
-    pub fn from_opening(opening: Vec<Turn<N>>, komi: i32) -> Self {
+    /// Start the analysis from a position, after playing the opening.
+    pub fn from_opening(opening: Vec<Turn<N>>, start: Game<N>) -> Self {
+        let mut record = GameRecord::new(start);
+        for turn in opening.iter().cloned() {
+            record.play(turn).expect("opening should be legal");
+        }
         Analysis {
             move_info: vec![None; opening.len()],
-            played_turns: opening,
-            komi,
+            record,
             ..Default::default()
         }
     }
@@ -43,7 +49,7 @@ impl<const N: usize> Analysis<N> {
             .filter(|(_, node)| CANDIDATE_MOVE_RATIO < node.visits as f32 / top_visits as f32)
             .collect();
 
-        let ply = self.played_turns.len();
+        let ply = self.record.turns().len();
         let eval_perspective = if ply % 2 == 0 { 1. } else { -1. };
         for (candidate, candidate_node) in candidates {
             if candidate == &played_turn {
@@ -71,38 +77,35 @@ impl<const N: usize> Analysis<N> {
             policy: child.policy,
             visits: child.visits,
         }));
-        self.played_turns.push(played_turn)
+        self.record
+            .play(played_turn)
+            .expect("played turn should be legal")
     }
 }
 
 impl<const N: usize> ToPTN for Analysis<N> {
     fn to_ptn(&self) -> String {
-        let mut out = format!("[Size \"{N}\"]\n[Komi \"{}\"]\n", self.komi);
-        let mut turn_iter = self.played_turns.iter();
-        let mut info_iter = self.move_info.iter();
-        let mut move_num = 1;
-        while let Some(white) = turn_iter.next() {
-            // add white turn
-            out.push_str(&format!("{move_num}. "));
-            out.push_str(&white.to_ptn());
-
-            // maybe add eval
-            if let Some(Some(info)) = info_iter.next() {
-                out.push_str(&format!(" {{{}}}", info.to_ptn()));
+        let mut ptn = PtnGame::from(&self.record);
+        let mut game = self.record.start().clone();
+        for (ply, info) in ptn.plies.iter_mut().zip(&self.move_info) {
+            // mark turns which threaten to win next turn, and forced wins
+            let colour = game.to_move;
+            game.play(ply.turn.clone())
+                .expect("recorded turns should be legal");
+            if game.winner() == GameResult::Ongoing && !game.winning_moves(colour).is_empty() {
+                if game.tinue_with_budget(colour, TINUE_DEPTH, TINUE_NODES).is_some() {
+                    ply.annotation.push('"');
+                } else {
+                    ply.annotation.push('\'');
+                }
             }
-            out.push(' ');
 
-            // maybe add black move
-            if let Some(black) = turn_iter.next() {
-                out.push_str(&black.to_ptn());
-                // maybe add eval
-                if let Some(Some(info)) = info_iter.next() {
-                    out.push_str(&format!(" {{{}}}", info.to_ptn()));
-                }
+            // add evals as comments
+            if let Some(info) = info {
+                ply.comments.push(info.to_ptn());
             }
-            out.push('\n');
-            move_num += 1;
         }
+        let mut out = ptn.to_ptn();
 
         for branch in self.branches.iter() {
             out.push('\n'); // empty line before branch
@@ -58,7 +58,7 @@ where
         game: &Game<N>,
         network: &'a Network<N>,
         opening: Vec<Turn<N>>,
-        komi: i32,
+        start: Game<N>,
         batch: u32,
     ) -> Self {
         let (request_tx, request_rx) = channel();
@@ -68,7 +68,7 @@ where
             node: Default::default(),
             network,
             examples: Vec::new(),
-            analysis: Analysis::from_opening(opening, komi),
+            analysis: Analysis::from_opening(opening, start),
             request_tx,
             response_rx,
             batch,
@@ -1,6 +1,8 @@
+use tak::Komi;
+
 // game settings
 pub const N: usize = 5;
-pub const KOMI: i32 = 2;
+pub const KOMI: Komi = Komi::from_half_points(4); // 2 flats
 
 // model
 pub const RES_BLOCKS: usize = 8;
@@ -26,6 +28,11 @@ pub const BATCH_SIZE: i64 = 10_000;
 pub const LEARNING_RATE: f64 = 1e-4;
 pub const WEIGHT_DECAY: f64 = 1e-4;
 
+// tinue search, in turns of the player looking for a forced win
+pub const TINUE_DEPTH: usize = 2;
+// positions searched for a tinue before giving up
+pub const TINUE_NODES: usize = 20_000;
+
 // pit
 pub const WIN_RATE_THRESHOLD: f64 = 0.55;
 pub const PIT_MATCHES: usize = 80;
@@ -41,7 +41,6 @@ pub struct Example<const N: usize> {
 impl<const N: usize> Example<N>
 where
     Turn<N>: Lut,
-    [[Option<Tile>; N]; N]: Default,
 {
     pub fn to_tensors(&self) -> Vec<(Tensor, Tensor, f32)> {
         let mut pi = [
@@ -93,10 +92,7 @@ pub fn save_examples<const N: usize, P: AsRef<Path>>(examples: &[Example<N>], pa
 }
 
 // TODO clean this up
-pub fn load_examples<const N: usize>(path: &str) -> Result<Vec<Example<N>>, Box<dyn Error>>
-where
-    [[Option<Tile>; N]; N]: Default,
-{
+pub fn load_examples<const N: usize>(path: &str) -> Result<Vec<Example<N>>, Box<dyn Error>> {
     let mut file = File::open(path)?;
     let mut s = String::new();
     file.read_to_string(&mut s)?;
@@ -104,35 +100,7 @@ where
     s.split_terminator('\n')
         .map(|example| {
             let mut chunks = example.split(';');
-            let mut tps = chunks.next().expect("missing board").split(' ');
-
-            // TODO put this ugly code into different functions, clean it up a bit
-            // MOVE IT TO FromTPS for Game
-            let board = Board::from_tps(tps.next().expect("missing board")).unwrap();
-            let to_move = Colour::from_ptn(tps.next().expect("missing to_move")).unwrap();
-            let ply = (tps.next().expect("missing move number").parse::<u64>().unwrap() - 1) * 2
-                + match to_move {
-                    Colour::White => 0,
-                    Colour::Black => 1,
-                };
-            let mut white_reserves = tps.next().expect("missing white reserves").split('/');
-            let white_stones = white_reserves.next().unwrap()[1..].parse().unwrap();
-            let white_caps = white_reserves.next().unwrap().replace(')', "").parse().unwrap();
-            let mut black_reserves = tps.next().expect("missing black reserves").split('/');
-            let black_stones = black_reserves.next().unwrap()[1..].parse().unwrap();
-            let black_caps = black_reserves.next().unwrap().replace(')', "").parse().unwrap();
-            let komi = tps.next().expect("missing komi").parse::<i32>().unwrap();
-
-            let game = Game {
-                board,
-                to_move,
-                white_caps,
-                black_caps,
-                white_stones,
-                black_stones,
-                ply,
-                komi,
-            };
+            let game = Game::from_tps(chunks.next().expect("missing board")).expect("invalid game TPS");
 
             let result = chunks
                 .next()
@@ -20,7 +20,6 @@ impl<const N: usize> Network<N> {
     pub fn train(&mut self, examples: &[Example<N>])
     where
         Turn<N>: Lut,
-        [[Option<Tile>; N]; N]: Default,
     {
         println!("starting training with {} examples", examples.len());
 
@@ -43,7 +42,6 @@ impl<const N: usize> Network<N> {
     fn train_inner(&mut self, opt: &mut Optimizer, examples: &[&Example<N>])
     where
         Turn<N>: Lut,
-        [[Option<Tile>; N]; N]: Default,
     {
         // batch examples
         let mut batch_iter = {
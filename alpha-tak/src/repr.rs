@@ -14,6 +14,7 @@ pub const fn input_channels(n: usize) -> usize {
     board_channels(n) + COLOUR_CHANNEL + FCD_CHANNEL + 2 * stones as usize + 2 * capstones as usize
 }
 
+/// Size of the policy, the same as [`PackedTurn::COUNT`].
 pub const fn moves_dims(n: usize) -> usize {
     match n {
         3 => 2 * 3 * 3 + 108,   // 126
@@ -59,7 +60,7 @@ fn board_repr<const N: usize>(board: &Board<N>, to_move: Colour) -> Tensor {
                 let pos = Pos { x, y };
                 if let Some(tile) = &board[pos] {
                     let board_offset = N * y + x;
-                    if let Some(&colour) = tile.stack.iter().rev().nth(n) {
+                    if let Some(colour) = tile.stack.iter().rev().nth(n) {
                         positions.push(
                             (board_offset + N * N * (6 + 2 * n + if to_move == colour { 0 } else { 1 }))
                                 as i64,
@@ -76,11 +77,17 @@ fn board_repr<const N: usize>(board: &Board<N>, to_move: Colour) -> Tensor {
     zeros.put_(&index, &ones, false)
 }
 
-fn create_reserves_tensor<const N: usize>(stones: u8, max: u8) -> Tensor {
-    let mut reserves = Tensor::zeros(&[max as i64, N as i64, N as i64], FLOAT_CPU);
-    if stones > 0 {
+/// One-hot encoding of the pieces left, with one channel per piece in the
+/// standard reserves. Reserves from other rules are scaled to fit, so that
+/// full reserves always take the last channel.
+fn create_reserves_tensor<const N: usize>(pieces: u8, start: u8, channels: u8) -> Tensor {
+    let mut reserves = Tensor::zeros(&[channels as i64, N as i64, N as i64], FLOAT_CPU);
+    if pieces > 0 && channels > 0 {
+        // rounding up keeps a single piece apart from none
+        let start = start.max(pieces) as usize;
+        let channel = (pieces as usize * channels as usize + start - 1) / start;
         reserves = reserves.index_put_(
-            &[Some(Tensor::of_slice(&[(stones - 1) as i64])), None, None],
+            &[Some(Tensor::of_slice(&[(channel - 1) as i64])), None, None],
             &Tensor::ones(&[N as i64, N as i64], FLOAT_CPU),
             false,
         );
@@ -90,10 +97,15 @@ fn create_reserves_tensor<const N: usize>(stones: u8, max: u8) -> Tensor {
 
 fn reserves_repr<const N: usize>(game: &Game<N>) -> (Tensor, Tensor, Tensor, Tensor) {
     let (stones, capstones) = default_starting_stones(N);
-    let white_stones = create_reserves_tensor::<N>(game.white_stones, stones);
-    let black_stones = create_reserves_tensor::<N>(game.black_stones, stones);
-    let white_caps = create_reserves_tensor::<N>(game.white_caps, capstones);
-    let black_caps = create_reserves_tensor::<N>(game.black_caps, capstones);
+    let Rules {
+        stones: start_stones,
+        caps: start_caps,
+        ..
+    } = game.rules;
+    let white_stones = create_reserves_tensor::<N>(game.white_stones, start_stones, stones);
+    let black_stones = create_reserves_tensor::<N>(game.black_stones, start_stones, stones);
+    let white_caps = create_reserves_tensor::<N>(game.white_caps, start_caps, capstones);
+    let black_caps = create_reserves_tensor::<N>(game.black_caps, start_caps, capstones);
 
     if game.to_move == Colour::White {
         (white_stones, black_stones, white_caps, black_caps)
@@ -118,8 +130,8 @@ pub fn game_repr<const N: usize>(game: &Game<N>) -> Tensor {
     );
 
     // layer for fcd (+ komi)
-    let fcd = game.board.flat_diff() - game.komi;
-    let relative_fcd = fcd as f64 / (N * N) as f64;
+    let half_fcd = 2 * game.board.flat_diff() - game.rules.komi.half_points();
+    let relative_fcd = half_fcd as f64 / (2 * N * N) as f64;
     let fcd_layer = Tensor::full(&layer_shape, relative_fcd, FLOAT_CPU);
 
     Tensor::cat(
@@ -11,6 +11,9 @@ pub struct Args {
     /// Path to PTN game file
     #[clap(short, long)]
     pub ptn_file: Option<String>,
+    /// Set up the position to analyse by hand
+    #[clap(short, long)]
+    pub edit: bool,
     /// Disable GPU usage
     #[clap(short, long)]
     pub no_gpu: bool,
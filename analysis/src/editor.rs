@@ -0,0 +1,96 @@
+use std::io::{stdin, stdout, Write};
+
+use tak::*;
+
+const HELP: &str = "\
+Set up the position with these commands:
+  <square> <tile>                put a stack on a square, written like TPS, e.g. `c3 12C`
+  <square> x                     clear a square
+  to_move <1|2>                  set the player to move
+  ply <ply|auto>                 set the ply, or work it out from the board
+  komi <komi>                    set the komi, e.g. `2.5`
+  reserves <1|2> <stones> <caps> set the reserves of a player
+  reserves auto                  work out the reserves from the board
+  tps <tps>                      start over from a position
+  done                           analyse the position";
+
+/// Set up a position by hand, starting from this game.
+pub fn edit_position<const N: usize>(game: &Game<N>) -> Game<N> {
+    let mut builder = GameBuilder::from_game(game);
+    builder.derive_reserves().ply(None);
+    let mut last = game.clone();
+    println!("{HELP}");
+
+    loop {
+        match builder.build() {
+            Ok(game) => {
+                println!("{}{}", game.board.detailed(), game.to_tps());
+                last = game;
+            }
+            Err(err) => println!("not a legal position: {err}"),
+        }
+
+        print!("edit: ");
+        stdout().flush().unwrap();
+        let mut line = String::new();
+        if stdin().read_line(&mut line).unwrap() == 0 {
+            return last;
+        }
+        match edit(&mut builder, &line) {
+            Ok(true) => match builder.build() {
+                Ok(game) => return game,
+                Err(err) => println!("cannot analyse yet: {err}"),
+            },
+            Ok(false) => {}
+            Err(err) => println!("{err}, type `help` for the commands"),
+        }
+    }
+}
+
+/// Apply one command, returning whether editing is done.
+fn edit<const N: usize>(builder: &mut GameBuilder<N>, line: &str) -> Result<bool, String> {
+    let words: Vec<_> = line.split_whitespace().collect();
+    match words.as_slice() {
+        ["done"] => return Ok(true),
+        ["help"] => println!("{HELP}"),
+        ["to_move", colour] => {
+            builder.to_move(Colour::from_ptn(colour).map_err(|err| err.to_string())?);
+        }
+        ["ply", "auto"] => {
+            builder.ply(None);
+        }
+        ["ply", ply] => {
+            builder.ply(ply.parse::<u64>().map_err(|_| format!("cannot read ply {ply}"))?);
+        }
+        ["komi", komi] => {
+            builder.komi(komi.parse::<Komi>().map_err(|err| err.to_string())?);
+        }
+        ["reserves", "auto"] => {
+            builder.derive_reserves();
+        }
+        ["reserves", colour, stones, caps] => {
+            let colour = Colour::from_ptn(colour).map_err(|err| err.to_string())?;
+            let count = |s: &str| s.parse().map_err(|_| format!("cannot read reserves {s}"));
+            builder.reserves(colour, count(stones)?, count(caps)?);
+        }
+        ["tps", ..] => {
+            let tps = line.trim().trim_start_matches("tps").trim();
+            let game = Game::<N>::from_tps(tps).map_err(|err| err.to_string())?;
+            *builder = GameBuilder::from_game(&game);
+        }
+        [square, "x"] => {
+            builder.clear(Pos::from_ptn(square).map_err(|err| err.to_string())?);
+        }
+        [square, tile] => {
+            let pos = Pos::from_ptn(square).map_err(|err| err.to_string())?;
+            let tile = Tile::from_tps(tile).map_err(|err| err.to_string())?;
+            let below = tile.stack.iter().map(|colour| Piece {
+                colour,
+                shape: Shape::Flat,
+            });
+            builder.set_stack(pos, below.chain([tile.top]));
+        }
+        _ => return Err("unknown command".to_string()),
+    }
+    Ok(false)
+}
@@ -8,7 +8,7 @@ use std::{
 use alpha_tak::{
     analysis::Analysis,
     batch_player::BatchPlayer,
-    config::{KOMI, N},
+    config::{KOMI, N, TINUE_DEPTH},
     model::network::Network,
     use_cuda,
 };
@@ -21,6 +21,7 @@ use tak::*;
 static GLOBAL: MiMalloc = MiMalloc;
 
 mod cli;
+mod editor;
 
 fn main() {
     let args = Args::parse();
@@ -48,7 +49,10 @@ fn main() {
     }
 
     let mut game = Game::<N>::with_komi(KOMI);
-    let mut player = BatchPlayer::new(&game, &network, vec![], game.komi, args.batch_size);
+    if args.edit {
+        game = editor::edit_position(&game);
+    }
+    let mut player = BatchPlayer::new(&game, &network, vec![], game.clone(), args.batch_size);
 
     while matches!(game.winner(), GameResult::Ongoing) {
         // Get input from user.
@@ -65,9 +69,14 @@ fn main() {
                 clear_screen();
                 if input.chars().all(char::is_whitespace) {
                     println!("{}", player.debug(Some(5)));
+                    print_tinue(&game);
                 } else {
-                    try_play_move(&mut player, &mut game, input).unwrap_or_else(|err| println!("{err}"));
+                    try_play_move(&mut player, &mut game, input).unwrap_or_else(|err| match err {
+                        Error::ParseError { .. } => println!("could not read move: {err}"),
+                        _ => println!("illegal move: {err}"),
+                    });
                 }
+                println!("{}", game.board.detailed());
                 break;
             }
         }
@@ -79,6 +88,16 @@ fn main() {
     }
 }
 
+fn print_tinue(game: &Game<N>) {
+    match game.tinue(game.to_move, TINUE_DEPTH) {
+        Some(line) => println!(
+            "tinue: {}",
+            line.iter().map(Turn::to_ptn).collect::<Vec<_>>().join(" ")
+        ),
+        None => println!("no tinue within depth {TINUE_DEPTH}"),
+    }
+}
+
 fn clear_screen() {
     print!("{esc}[2J{esc}[1;1H", esc = 27 as char);
     stdout().flush().unwrap()
@@ -92,7 +111,7 @@ fn get_input() -> String {
     line
 }
 
-fn try_play_move(player: &mut BatchPlayer<'_, 5>, game: &mut Game<5>, input: String) -> StrResult<()> {
+fn try_play_move(player: &mut BatchPlayer<'_, 5>, game: &mut Game<5>, input: String) -> TakResult<()> {
     let turn = Turn::from_ptn(&input)?;
     let mut copy = game.clone();
     copy.play(turn.clone())?;
@@ -102,7 +121,7 @@ fn try_play_move(player: &mut BatchPlayer<'_, 5>, game: &mut Game<5>, input: Str
 
 fn analysis_for_file(network: &Network<N>, turns: Vec<Turn<N>>, batch_size: u32) -> Analysis<N> {
     let mut game = Game::with_komi(KOMI);
-    let mut player = BatchPlayer::new(&game, network, vec![], game.komi, batch_size);
+    let mut player = BatchPlayer::new(&game, network, vec![], game.clone(), batch_size);
 
     for turn in turns {
         println!("Analysing {}", turn.to_ptn());
@@ -0,0 +1,24 @@
+use clap::Parser;
+
+/// Count the positions reachable from a Tak position, split up by the first
+/// turn
+#[derive(Parser)]
+pub struct Args {
+    /// Size of the board
+    #[clap(short, long, default_value_t = 5)]
+    pub size: usize,
+    /// Number of plies to look ahead. Games which end earlier count as one
+    /// position, so a game which is already over counts as 1
+    #[clap(short, long, default_value_t = 3)]
+    pub depth: usize,
+    /// Position to count from
+    #[clap(short, long, conflicts_with = "ptn")]
+    pub tps: Option<String>,
+    /// PTN file whose final position is counted from
+    #[clap(short, long)]
+    pub ptn: Option<String>,
+    /// Count transpositions from a table, which is faster for deeper
+    /// searches but takes a lot of memory
+    #[clap(long)]
+    pub table: bool,
+}
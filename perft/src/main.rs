@@ -0,0 +1,59 @@
+use std::{fs, time::Instant};
+
+use clap::Parser;
+use cli::Args;
+use mimalloc::MiMalloc;
+use tak::*;
+
+#[global_allocator]
+static GLOBAL: MiMalloc = MiMalloc;
+
+mod cli;
+
+fn main() {
+    let args = Args::parse();
+    let result = match args.size {
+        3 => run::<3>(&args),
+        4 => run::<4>(&args),
+        5 => run::<5>(&args),
+        6 => run::<6>(&args),
+        7 => run::<7>(&args),
+        8 => run::<8>(&args),
+        size => {
+            println!("unsupported board size {size}");
+            return;
+        }
+    };
+    if let Err(err) = result {
+        println!("could not read position: {err}");
+    }
+}
+
+fn run<const N: usize>(args: &Args) -> TakResult<()> {
+    let game = match (&args.tps, &args.ptn) {
+        (Some(tps), _) => Game::<N>::from_tps(tps)?,
+        (None, Some(path)) => GameRecord::<N>::from_ptn(&fs::read_to_string(path)?)?
+            .game()
+            .clone(),
+        (None, None) => Game::default(),
+    };
+
+    let start = Instant::now();
+    let divide = perft_divide(&game, args.depth, args.table);
+    for (turn, count) in &divide {
+        println!("{} {count}", turn.to_ptn());
+    }
+    // like a position at depth 0, a game which is over counts as one
+    let total = if divide.is_empty() {
+        1
+    } else {
+        divide.iter().map(|(_, count)| count).sum()
+    };
+    println!();
+    println!(
+        "{total} positions at depth {} in {:.2?}",
+        args.depth,
+        start.elapsed()
+    );
+    Ok(())
+}
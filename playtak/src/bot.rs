@@ -4,7 +4,12 @@ use std::{
     time::{Duration, Instant},
 };
 
-use alpha_tak::{batch_player::BatchPlayer, config::KOMI, model::network::Network, sys_time};
+use alpha_tak::{
+    batch_player::BatchPlayer,
+    config::{KOMI, TINUE_DEPTH, TINUE_NODES},
+    model::network::Network,
+    sys_time,
+};
 use tak::*;
 use tokio::sync::mpsc::{error::TryRecvError, UnboundedReceiver, UnboundedSender};
 
@@ -23,9 +28,8 @@ pub fn run_bot(args: Args, tx: UnboundedSender<Message>, mut rx: UnboundedReceiv
         Network::<5>::load(model_path).unwrap_or_else(|_| panic!("could not load model at {model_path}"));
 
     'game_loop: loop {
-        let mut game = Game::<5>::with_komi(KOMI);
-        let mut player = BatchPlayer::new(&game, &network, vec![], game.komi, 64);
-        let mut last_move: String = String::new();
+        let mut record = GameRecord::new(Game::<5>::with_komi(KOMI));
+        let mut player = BatchPlayer::new(record.game(), &network, vec![], record.game().clone(), 64);
         let mut ponder_rollouts = 0;
 
         'turn_loop: loop {
@@ -40,28 +44,27 @@ pub fn run_bot(args: Args, tx: UnboundedSender<Message>, mut rx: UnboundedReceiv
                     ponder_rollouts = 0;
 
                     println!("A move has been requested.");
+                    let game = record.game();
                     if game.winner() != GameResult::Ongoing {
                         tx.send(Message::GameEnded).unwrap();
                         continue;
                     }
 
-                    // Check for moves that win on the spot.
-                    let mut insta_win = None;
-                    for turn in game.possible_turns() {
-                        let mut clone = game.clone();
-                        clone.play(turn.clone()).unwrap();
-                        if matches!(clone.winner(), GameResult::Winner { colour, .. } if colour == game.to_move)
-                        {
-                            insta_win = Some(turn);
-                            break;
-                        }
-                    }
+                    // Check for moves that win on the spot, by road or on flats.
+                    let insta_win = game
+                        .winning_moves(game.to_move)
+                        .into_iter()
+                        .next()
+                        .or_else(|| game.flat_winning_moves(game.to_move).into_iter().next());
 
+                    // The book is written for the canonical position.
                     let mut book = None;
                     if game.ply == 1 {
+                        let (_, symmetry) = game.canonical();
+                        let last_move = record.turns().last().unwrap().clone().symmetry(symmetry).to_ptn();
                         for opening in OPENING_BOOK {
                             if opening.0 == last_move {
-                                book = Some(Turn::from_ptn(opening.1).unwrap());
+                                book = Some(Turn::from_ptn(opening.1).unwrap().inverse_symmetry(symmetry));
                                 break;
                             }
                         }
@@ -70,41 +73,47 @@ pub fn run_bot(args: Args, tx: UnboundedSender<Message>, mut rx: UnboundedReceiv
                     // Pick turn to play.
                     let turn = if game.ply == 0 {
                         let first = Turn::from_ptn(WHITE_FIRST_MOVE).unwrap();
-                        player.play_move(&game, &first);
+                        player.play_move(game, &first);
                         first
                     } else if let Some(game_winning_turn) = insta_win {
-                        player.play_move(&game, &game_winning_turn);
+                        player.play_move(game, &game_winning_turn);
                         game_winning_turn
+                    } else if let Some(line) = game.tinue_with_budget(game.to_move, TINUE_DEPTH, TINUE_NODES)
+                    {
+                        println!(
+                            "Found tinue: {}",
+                            line.iter().map(Turn::to_ptn).collect::<Vec<_>>().join(" ")
+                        );
+                        let tinue_turn = line.into_iter().next().unwrap();
+                        player.play_move(game, &tinue_turn);
+                        tinue_turn
                     } else if let Some(book_turn) = book {
-                        player.play_move(&game, &book_turn);
+                        player.play_move(game, &book_turn);
                         book_turn
                     } else {
                         println!("Doing rollouts...");
                         // Do rollouts for a set amount of time.
                         let start = Instant::now();
                         while Instant::now().duration_since(start) < Duration::from_secs(args.time_to_think) {
-                            player.rollout(&game);
+                            player.rollout(game);
                         }
                         print!("{}", player.debug(Some(5)));
 
-                        player.pick_move(&game, true)
+                        player.pick_move(game, true)
                     };
 
                     println!("=== Network played  {}", turn.to_ptn());
-                    tx.send(Message::Turn(turn.to_ptn())).unwrap();
-                    game.play(turn).unwrap();
+                    tx.send(Message::Turn(turn.clone())).unwrap();
+                    record.play(turn).unwrap();
                 }
 
                 // Opponent played a move.
-                Ok(Message::Turn(s)) => {
+                Ok(Message::Turn(turn)) => {
                     print!("{}", player.debug(Some(5)));
-                    println!("=== Opponent played {s}");
-
-                    let turn = Turn::from_ptn(&s).unwrap();
-                    last_move = s;
+                    println!("=== Opponent played {}", turn.to_ptn());
 
-                    player.play_move(&game, &turn);
-                    game.play(turn).unwrap()
+                    player.play_move(record.game(), &turn);
+                    record.play(turn).unwrap()
                 }
 
                 // Game ended.
@@ -115,7 +124,7 @@ pub fn run_bot(args: Args, tx: UnboundedSender<Message>, mut rx: UnboundedReceiv
                 // Ponder.
                 Err(TryRecvError::Empty) => {
                     ponder_rollouts += 1;
-                    player.rollout(&game);
+                    player.rollout(record.game());
                     thread::yield_now()
                 }
 
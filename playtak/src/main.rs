@@ -19,7 +19,8 @@ mod playtak;
 mod seek;
 
 const WHITE_FIRST_MOVE: &str = "e5";
-const OPENING_BOOK: [(&str, &str); 4] = [("a1", "e5"), ("a5", "e1"), ("e1", "a5"), ("e5", "a1")];
+/// Replies to the first move, in the canonical position after it.
+const OPENING_BOOK: [(&str, &str); 1] = [("e5", "a1")];
 
 const PONDER_ROLLOUT_LIMIT: u64 = 10_000;
 
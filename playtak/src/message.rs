@@ -1,6 +1,8 @@
+use tak::Turn;
+
 #[derive(Debug)]
 pub enum Message {
     MoveRequest,
-    Turn(String),
+    Turn(Turn<5>),
     GameEnded,
 }
@@ -9,7 +9,17 @@ pub async fn create_seek(client: &mut Client, color: Color, initial_time: Durati
             SeekParameters::new(
                 None,
                 color,
-                GameParameters::new(5, initial_time, increment, 2 * KOMI, 21, 1, false, false).unwrap(),
+                GameParameters::new(
+                    5,
+                    initial_time,
+                    increment,
+                    KOMI.half_points(),
+                    21,
+                    1,
+                    false,
+                    false,
+                )
+                .unwrap(),
             )
             .unwrap(),
         )
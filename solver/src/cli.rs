@@ -0,0 +1,24 @@
+use clap::Parser;
+use tak::Komi;
+
+/// Solve Tak positions with proof-number search
+#[derive(Parser)]
+pub struct Args {
+    /// Size of the board
+    #[clap(short, long, default_value_t = 3)]
+    pub size: usize,
+    /// Position to solve, the starting position if left out
+    #[clap(short, long)]
+    pub tps: Option<String>,
+    /// Komi for the game, replacing the one in the TPS
+    #[clap(short, long)]
+    pub komi: Option<Komi>,
+    /// Number of plies after which the game is a draw, replacing the one in
+    /// the TPS
+    #[clap(long, conflicts_with = "no-turn-limit")]
+    pub turn_limit: Option<u64>,
+    /// Solve without a turn limit, which is much faster since positions
+    /// reached at different plies can share their result
+    #[clap(long)]
+    pub no_turn_limit: bool,
+}
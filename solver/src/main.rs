@@ -0,0 +1,62 @@
+use std::time::Instant;
+
+use clap::Parser;
+use cli::Args;
+use mimalloc::MiMalloc;
+use tak::*;
+
+#[global_allocator]
+static GLOBAL: MiMalloc = MiMalloc;
+
+mod cli;
+
+fn main() {
+    let args = Args::parse();
+    let result = match args.size {
+        3 => solve::<3>(&args),
+        4 => solve::<4>(&args),
+        5 => solve::<5>(&args),
+        6 => solve::<6>(&args),
+        7 => solve::<7>(&args),
+        8 => solve::<8>(&args),
+        size => {
+            println!("unsupported board size {size}");
+            return;
+        }
+    };
+    if let Err(err) = result {
+        println!("could not read position: {err}");
+    }
+}
+
+fn solve<const N: usize>(args: &Args) -> TakResult<()> {
+    let mut game = match &args.tps {
+        Some(tps) => Game::<N>::from_tps(tps)?,
+        None => Game::default(),
+    };
+    if let Some(komi) = args.komi {
+        game.rules.komi = komi;
+    }
+    if args.no_turn_limit {
+        game.rules.turn_limit = None;
+    } else if let Some(turn_limit) = args.turn_limit {
+        game.rules.turn_limit = Some(turn_limit);
+    }
+
+    let start = Instant::now();
+    let mut solver = Solver::new();
+    let solution = solver.solve(&game);
+    println!(
+        "{solution:?} for {:?}, solved in {:.2?} with {} positions in the table",
+        game.to_move,
+        start.elapsed(),
+        solver.len()
+    );
+
+    let mut record = GameRecord::new(game.clone());
+    for turn in solver.principal_line(&game) {
+        record.play(turn)?;
+    }
+    println!("{}", PtnGame::from(&record).to_ptn());
+    Ok(())
+}
@@ -0,0 +1,259 @@
+#![feature(test)]
+
+extern crate test;
+
+use std::cmp::min;
+
+use arrayvec::ArrayVec;
+use tak::*;
+use test::{black_box, Bencher};
+
+/// The positions from `tests/pertf.rs`.
+fn positions() -> Vec<Game<5>> {
+    let lines: [&[&str]; 3] = [
+        &["d3", "c3", "c4", "1d3<", "1c4-", "Sc4"],
+        &[
+            "c2", "c3", "d3", "b3", "c4", "1c2+", "1d3<", "1b3>", "1c4-", "Cc2", "a1", "1c2+", "a2",
+        ],
+        &[
+            "c4", "c2", "d2", "c3", "b2", "d3", "1d2+", "b3", "d2", "b4", "1c2+", "1b3>", "2d3<", "1c4-",
+            "d4", "5c3<23", "c2", "c4", "1d4<", "d3", "1d2+", "1c3+", "Cc3", "2c4>", "1c3<", "d2", "c3",
+            "1d2+", "1c3+", "1b4>", "2b3>11", "3c4-12", "d2", "c4", "b4", "c5", "1b3>", "1c4<", "3c3-", "e5",
+            "e2",
+        ],
+    ];
+    lines
+        .into_iter()
+        .map(|line| {
+            let mut game = Game::default();
+            game.play_ptn_moves(line).unwrap();
+            game
+        })
+        .collect()
+}
+
+fn perft_possible_turns<const N: usize>(game: &mut Game<N>, depth: usize) -> usize {
+    if depth == 0 {
+        return 1;
+    }
+    let mut count = 0;
+    for turn in game.possible_turns() {
+        let undo = game.play_undoable(turn).unwrap();
+        count += perft_possible_turns(game, depth - 1);
+        game.undo(undo);
+    }
+    count
+}
+
+/// The generator from before `MoveList`, kept as the baseline for the
+/// benchmarks below.
+fn baseline_turns<const N: usize>(game: &Game<N>) -> Vec<Turn<N>> {
+    let mut turns = Vec::new();
+
+    // can only place opponent's flat on the first two plies
+    if game.swap() {
+        for pos in (0..N).flat_map(|x| (0..N).map(move |y| Pos { x, y })) {
+            if game.board[pos].is_none() {
+                turns.push(Turn::Place {
+                    pos,
+                    shape: Shape::Flat,
+                });
+            }
+        }
+        return turns;
+    }
+
+    for pos in (0..N).flat_map(|x| (0..N).map(move |y| Pos { x, y })) {
+        if let Some(tile) = &game.board[pos] {
+            if tile.top.colour == game.to_move {
+                baseline_moves(game, &mut turns, pos, tile);
+            }
+        } else {
+            let (stones, caps) = game.get_counts();
+            if stones > 0 {
+                turns.push(Turn::Place {
+                    pos,
+                    shape: Shape::Flat,
+                });
+                turns.push(Turn::Place {
+                    pos,
+                    shape: Shape::Wall,
+                });
+            }
+            if caps > 0 {
+                turns.push(Turn::Place {
+                    pos,
+                    shape: Shape::Capstone,
+                });
+            }
+        }
+    }
+    turns
+}
+
+fn baseline_moves<const N: usize>(game: &Game<N>, turns: &mut Vec<Turn<N>>, pos: Pos<N>, tile: &Tile) {
+    for neighbour in pos.neighbors() {
+        let direction = (neighbour - pos).unwrap();
+        let max_carry = min(tile.size(), N);
+        for drop_choices in 0..max_carry {
+            let capstone = matches!(tile.top.shape, Shape::Capstone);
+            let mut tries = vec![(neighbour, drop_choices, ArrayVec::new())];
+            let mut possible_moves = Vec::new();
+            while let Some((current, drop_choices, mut moves)) = tries.pop() {
+                #[rustfmt::skip]
+                let can_drop = match game.board[current] {
+                    None => true,
+                    Some(Tile {top: Piece {shape: Shape::Flat, ..}, ..}) => true,
+                    Some(Tile {top: Piece {shape: Shape::Wall, ..}, ..})
+                        if drop_choices == 0 && capstone => true,
+                    _ => false,
+                };
+
+                if !can_drop {
+                    continue;
+                }
+                if drop_choices == 0 {
+                    moves.push(false);
+                    possible_moves.push(moves);
+                    continue;
+                }
+
+                if let Some(next) = current.step(direction) {
+                    let mut copy = moves.clone();
+                    copy.push(true);
+                    tries.push((next, drop_choices - 1, copy));
+                }
+                moves.push(false);
+                tries.push((current, drop_choices - 1, moves));
+            }
+
+            turns.extend(possible_moves.into_iter().map(|moves| Turn::Move {
+                pos,
+                direction,
+                moves,
+            }));
+        }
+    }
+}
+
+fn perft_baseline<const N: usize>(game: &mut Game<N>, depth: usize) -> usize {
+    if depth == 0 {
+        return 1;
+    }
+    let mut count = 0;
+    for turn in baseline_turns(game) {
+        let undo = game.play_undoable(turn).unwrap();
+        count += perft_baseline(game, depth - 1);
+        game.undo(undo);
+    }
+    count
+}
+
+#[bench]
+fn baseline(b: &mut Bencher) {
+    let games = positions();
+    for game in &games {
+        assert_eq!(baseline_turns(game).len(), game.possible_turns().len());
+    }
+    b.iter(|| {
+        for game in &games {
+            black_box(baseline_turns(game));
+        }
+    });
+}
+
+#[bench]
+fn perft_2_baseline(b: &mut Bencher) {
+    let mut games = positions();
+    b.iter(|| {
+        for game in &mut games {
+            black_box(perft_baseline(game, 2));
+        }
+    });
+}
+
+#[bench]
+fn possible_turns(b: &mut Bencher) {
+    let games = positions();
+    b.iter(|| {
+        for game in &games {
+            black_box(game.possible_turns());
+        }
+    });
+}
+
+#[bench]
+fn perft_2_possible_turns(b: &mut Bencher) {
+    let mut games = positions();
+    b.iter(|| {
+        for game in &mut games {
+            black_box(perft_possible_turns(game, 2));
+        }
+    });
+}
+
+fn perft_move_list<const N: usize>(game: &mut Game<N>, lists: &mut [MoveList<N>]) -> usize {
+    let (list, rest) = match lists.split_first_mut() {
+        Some(split) => split,
+        None => return 1,
+    };
+    list.clear();
+    game.generate_turns(list);
+    let mut count = 0;
+    for turn in &*list {
+        let undo = game.play_undoable(turn.clone()).unwrap();
+        count += perft_move_list(game, rest);
+        game.undo(undo);
+    }
+    count
+}
+
+#[bench]
+fn move_list(b: &mut Bencher) {
+    let games = positions();
+    let mut list = MoveList::new();
+    b.iter(|| {
+        for game in &games {
+            list.clear();
+            game.generate_turns(&mut list);
+            black_box(&list);
+        }
+    });
+}
+
+#[bench]
+fn placements(b: &mut Bencher) {
+    let games = positions();
+    let mut list = MoveList::new();
+    b.iter(|| {
+        for game in &games {
+            list.clear();
+            game.generate_placements(&mut list);
+            black_box(&list);
+        }
+    });
+}
+
+#[bench]
+fn spreads(b: &mut Bencher) {
+    let games = positions();
+    let mut list = MoveList::new();
+    b.iter(|| {
+        for game in &games {
+            list.clear();
+            game.generate_spreads(&mut list);
+            black_box(&list);
+        }
+    });
+}
+
+#[bench]
+fn perft_2_move_list(b: &mut Bencher) {
+    let mut games = positions();
+    let mut lists = [MoveList::new(), MoveList::new()];
+    b.iter(|| {
+        for game in &mut games {
+            black_box(perft_move_list(game, &mut lists));
+        }
+    });
+}
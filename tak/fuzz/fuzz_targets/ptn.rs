@@ -0,0 +1,28 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use tak::*;
+use tak_fuzz::{check, play_checked, playout, seed};
+
+// Play the moves of a PTN game on 5x5, then finish it with random turns.
+fuzz_target!(|data: &[u8]| {
+    let ptn = match std::str::from_utf8(data).map(PtnGame::<5>::from_ptn) {
+        Ok(Ok(ptn)) => ptn,
+        _ => return,
+    };
+    let mut game = match ptn.start() {
+        Ok(game) => game,
+        Err(_) => return,
+    };
+    check(&game);
+
+    for ply in ptn.plies {
+        if game.winner() != GameResult::Ongoing {
+            break;
+        }
+        if !play_checked(&mut game, ply.turn) {
+            return;
+        }
+    }
+    playout(&mut game, seed(data));
+});
@@ -0,0 +1,22 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use tak::*;
+use tak_fuzz::{playout, seed};
+
+// Play out 6x6 positions read from TPS, when they are consistent to begin
+// with.
+fuzz_target!(|data: &[u8]| {
+    let mut game = match std::str::from_utf8(data).map(Game::<6>::from_tps) {
+        Ok(Ok(game)) => game,
+        _ => return,
+    };
+    assert_eq!(
+        Game::<6>::from_tps(&game.to_tps()).as_ref(),
+        Ok(&game),
+        "TPS round trip changed the game"
+    );
+    if game.check_invariants().is_ok() {
+        playout(&mut game, seed(data));
+    }
+});
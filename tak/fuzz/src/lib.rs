@@ -0,0 +1,71 @@
+//! Checks shared by the fuzz targets, which play through a game and check
+//! [`Game::check_invariants`] after every ply.
+//!
+//! Run a target from the `tak` directory with the seed inputs, like
+//! `cargo fuzz run ptn fuzz/corpus/ptn fuzz/seeds/ptn`.
+
+use tak::*;
+
+/// Panic with the position if the game is not consistent.
+pub fn check<const N: usize>(game: &Game<N>) {
+    if let Err(violation) = game.check_invariants() {
+        panic!("{violation} in {}", game.to_tps());
+    }
+}
+
+/// Play the turn if it is legal, checking the game afterwards and that
+/// undoing the turn gives back the game from before.
+/// Returns whether the turn was played.
+pub fn play_checked<const N: usize>(game: &mut Game<N>, turn: Turn<N>) -> bool {
+    let before = game.clone();
+    match game.play_undoable(turn) {
+        Ok(undo) => {
+            check(game);
+            let after = game.clone();
+            game.undo(undo);
+            assert_eq!(game, &before, "undo did not restore the game");
+            *game = after;
+            true
+        }
+        Err(_) => {
+            // an illegal turn may have been played halfway
+            *game = before;
+            false
+        }
+    }
+}
+
+/// Play random turns until the game ends, then check that the result
+/// stays the same.
+pub fn playout<const N: usize>(game: &mut Game<N>, mut seed: u64) {
+    while game.winner() == GameResult::Ongoing {
+        // xorshift
+        seed ^= seed << 13;
+        seed ^= seed >> 7;
+        seed ^= seed << 17;
+        let turns = game.possible_turns();
+        let turn = turns[(seed % turns.len() as u64) as usize].clone();
+        assert!(play_checked(game, turn), "possible turns should be legal");
+    }
+    check_result(game);
+}
+
+/// Check that the result of a finished game is the same when it is read
+/// back from TPS.
+pub fn check_result<const N: usize>(game: &Game<N>) {
+    let copy = Game::<N>::from_tps(&game.to_tps()).expect("TPS of a game should be valid");
+    assert_eq!(
+        copy.winner(),
+        game.winner(),
+        "result changed in {}",
+        game.to_tps()
+    );
+}
+
+/// Seed for a playout from the fuzz input.
+pub fn seed(data: &[u8]) -> u64 {
+    // FNV-1a, made odd so that xorshift never gets stuck at zero
+    data.iter().fold(0xcbf2_9ce4_8422_2325, |hash, &byte| {
+        (hash ^ byte as u64).wrapping_mul(0x100_0000_01b3)
+    }) | 1
+}
@@ -0,0 +1,120 @@
+use std::ops::{BitAnd, BitOr, Not};
+
+use crate::pos::Pos;
+
+/// A set of squares on the board, one bit per square.
+/// Bit `y * N + x` represents `Pos { x, y }`, so boards up to 8x8 are
+/// supported.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Bitboard<const N: usize>(pub u64);
+
+impl<const N: usize> Bitboard<N> {
+    /// Get a bitboard with every square of the board set.
+    pub const fn full() -> Self {
+        Bitboard(u64::MAX >> (64 - N * N))
+    }
+
+    pub const fn bit(pos: Pos<N>) -> u64 {
+        1 << (pos.y * N + pos.x)
+    }
+
+    pub const fn contains(self, pos: Pos<N>) -> bool {
+        self.0 & Self::bit(pos) != 0
+    }
+
+    #[must_use]
+    pub const fn with(self, pos: Pos<N>) -> Self {
+        Bitboard(self.0 | Self::bit(pos))
+    }
+
+    #[must_use]
+    pub const fn without(self, pos: Pos<N>) -> Self {
+        Bitboard(self.0 & !Self::bit(pos))
+    }
+
+    pub const fn is_empty(self) -> bool {
+        self.0 == 0
+    }
+
+    /// Get the number of squares in the set.
+    pub const fn count(self) -> u32 {
+        self.0.count_ones()
+    }
+
+    /// Get the squares in row `y`.
+    pub const fn row(y: usize) -> Self {
+        Bitboard(((1 << N) - 1) << (y * N))
+    }
+
+    /// Get the squares in column `x`.
+    pub const fn column(x: usize) -> Self {
+        let mut bits = 0;
+        let mut y = 0;
+        while y < N {
+            bits |= 1 << (y * N + x);
+            y += 1;
+        }
+        Bitboard(bits)
+    }
+
+    /// Add the orthogonal neighbors of every square in the set.
+    #[must_use]
+    pub const fn dilate(self) -> Self {
+        let bits = self.0;
+        let left = (bits & !Self::column(0).0) >> 1;
+        let right = (bits & !Self::column(N - 1).0) << 1;
+        Bitboard((bits | left | right | bits << N | bits >> N) & Self::full().0)
+    }
+
+    /// Get the squares of `within` which are connected to this set
+    /// through other squares of `within`.
+    #[must_use]
+    pub fn flood_fill(self, within: Self) -> Self {
+        let mut filled = self & within;
+        loop {
+            let next = filled.dilate() & within;
+            if next == filled {
+                return filled;
+            }
+            filled = next;
+        }
+    }
+
+    /// Iterate over the squares in the set.
+    pub fn positions(self) -> impl Iterator<Item = Pos<N>> {
+        let mut bits = self.0;
+        std::iter::from_fn(move || {
+            if bits == 0 {
+                return None;
+            }
+            let i = bits.trailing_zeros() as usize;
+            bits &= bits - 1;
+            Some(Pos { x: i % N, y: i / N })
+        })
+    }
+}
+
+impl<const N: usize> BitAnd for Bitboard<N> {
+    type Output = Self;
+
+    fn bitand(self, rhs: Self) -> Self::Output {
+        Bitboard(self.0 & rhs.0)
+    }
+}
+
+impl<const N: usize> BitOr for Bitboard<N> {
+    type Output = Self;
+
+    fn bitor(self, rhs: Self) -> Self::Output {
+        Bitboard(self.0 | rhs.0)
+    }
+}
+
+impl<const N: usize> Not for Bitboard<N> {
+    type Output = Self;
+
+    /// Complement within the board, bits outside of it stay unset.
+    fn not(self) -> Self::Output {
+        Bitboard(!self.0 & Self::full().0)
+    }
+}
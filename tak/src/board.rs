@@ -1,92 +1,188 @@
-use std::{
-    collections::HashSet,
-    fmt::Display,
-    ops::{Index, IndexMut},
-};
+use std::{fmt::Display, ops::Index};
+
+use arrayvec::ArrayVec;
 
 use crate::{
+    bitboard::Bitboard,
     colour::Colour,
     pos::Pos,
-    tile::{Piece, Shape, Tile},
+    tile::{Shape, Tile},
+    zobrist::{tile_hash, Zobrist, ZOBRIST_STACK_DEPTH},
 };
 
-#[derive(Clone, Debug)]
+/// The board keeps bitboards of the top pieces next to the tiles,
+/// so all modifications have to go through [`Board::set`].
+#[derive(Clone, Debug, PartialEq, Eq)]
 pub struct Board<const N: usize> {
     data: [[Option<Tile>; N]; N],
+    white: Bitboard<N>,
+    black: Bitboard<N>,
+    walls: Bitboard<N>,
+    caps: Bitboard<N>,
+    hash: u64,
 }
 
 impl<const N: usize> Board<N> {
+    /// Replace the tile at the position.
+    pub fn set(&mut self, pos: Pos<N>, tile: Option<Tile>) {
+        self.hash ^=
+            tile_hash(pos, self[pos], ZOBRIST_STACK_DEPTH) ^ tile_hash(pos, tile, ZOBRIST_STACK_DEPTH);
+        self.white = self.white.without(pos);
+        self.black = self.black.without(pos);
+        self.walls = self.walls.without(pos);
+        self.caps = self.caps.without(pos);
+        if let Some(Tile { top, .. }) = tile {
+            match top.colour {
+                Colour::White => self.white = self.white.with(pos),
+                Colour::Black => self.black = self.black.with(pos),
+            }
+            match top.shape {
+                Shape::Flat => {}
+                Shape::Wall => self.walls = self.walls.with(pos),
+                Shape::Capstone => self.caps = self.caps.with(pos),
+            }
+        }
+        self.data[pos.y][pos.x] = tile;
+    }
+
+    /// Squares with a top piece of this colour.
+    pub fn pieces(&self, colour: Colour) -> Bitboard<N> {
+        match colour {
+            Colour::White => self.white,
+            Colour::Black => self.black,
+        }
+    }
+
+    /// Squares with any piece on them.
+    pub fn occupied(&self) -> Bitboard<N> {
+        self.white | self.black
+    }
+
+    /// Squares with a flat on top.
+    pub fn flats(&self) -> Bitboard<N> {
+        self.occupied() & !(self.walls | self.caps)
+    }
+
+    /// Squares with a wall on top.
+    pub fn walls(&self) -> Bitboard<N> {
+        self.walls
+    }
+
+    /// Squares with a capstone on top.
+    pub fn capstones(&self) -> Bitboard<N> {
+        self.caps
+    }
+
+    /// Zobrist key of the board, kept up to date by [`Board::set`].
+    ///
+    /// Only the top [`ZOBRIST_STACK_DEPTH`] pieces under each top are
+    /// hashed, so boards with taller stacks which differ deeper down
+    /// collide on purpose. Check the board on every hit when using the key
+    /// for a table which has to be exact, or hash deeper with [`Zobrist`].
+    ///
+    /// [`ZOBRIST_STACK_DEPTH`]: crate::ZOBRIST_STACK_DEPTH
+    pub fn hash(&self) -> u64 {
+        self.hash
+    }
+
+    /// Compute the Zobrist key of the board from scratch.
+    pub fn compute_hash(&self) -> u64 {
+        Zobrist::default().board(self)
+    }
+
+    /// Count the stones and capstones of this colour on the board.
+    pub fn piece_counts(&self, colour: Colour) -> (usize, usize) {
+        let mut stones = 0;
+        for pos in self.occupied().positions() {
+            let tile = self[pos].unwrap();
+            stones += tile.stack.iter().filter(|&c| c == colour).count();
+            if tile.top.colour == colour && tile.top.shape != Shape::Capstone {
+                stones += 1;
+            }
+        }
+        let caps = (self.caps & self.pieces(colour)).count() as usize;
+        (stones, caps)
+    }
+
     pub fn empty(&self) -> bool {
-        self.data.iter().all(|row| row.iter().all(|x| x.is_none()))
+        self.occupied().is_empty()
     }
 
     pub fn full(&self) -> bool {
-        self.data.iter().all(|row| row.iter().all(|x| x.is_some()))
+        self.occupied() == Bitboard::full()
     }
 
     pub fn flat_diff(&self) -> i32 {
-        let mut diff = 0;
-        for row in &self.data {
-            row.iter().flatten().for_each(|tile| {
-                if matches!(tile.top.shape, Shape::Flat) {
-                    match tile.top.colour {
-                        Colour::White => diff += 1,
-                        Colour::Black => diff -= 1,
-                    }
-                }
-            });
-        }
-        diff
+        let flats = self.flats();
+        (flats & self.white).count() as i32 - (flats & self.black).count() as i32
+    }
+
+    /// Squares which can be part of a road for this colour.
+    pub fn road_pieces(&self, colour: Colour) -> Bitboard<N> {
+        self.pieces(colour) & !self.walls
     }
 
+    /// Check whether this colour has a road.
     pub fn find_paths(&self, colour: Colour) -> bool {
-        // check vertical paths
-        let mut seen = HashSet::new();
-        for x in 0..N {
-            let pos = Pos { x, y: 0 };
-            self.find_paths_recursive(pos, colour, &mut seen);
-        }
-        if (0..N).any(|x| seen.contains(&Pos { x, y: N - 1 })) {
-            return true;
-        }
-        // check horizontal paths
-        let mut seen = HashSet::new();
-        for y in 0..N {
-            let pos = Pos { x: 0, y };
-            self.find_paths_recursive(pos, colour, &mut seen);
-        }
-        (0..N).any(|y| seen.contains(&Pos { x: N - 1, y }))
+        let roads = self.road_pieces(colour);
+        let connects = |from: Bitboard<N>, to| !(from.flood_fill(roads) & to).is_empty();
+        connects(Bitboard::column(0), Bitboard::column(N - 1))
+            || connects(Bitboard::row(0), Bitboard::row(N - 1))
     }
 
-    fn find_paths_recursive(&self, pos: Pos<N>, colour: Colour, seen: &mut HashSet<Pos<N>>) {
-        if seen.contains(&pos) {
-            return;
-        }
-        if let Some(Tile {
-            top: Piece {
-                colour: piece_colour,
-                shape,
-            },
-            ..
-        }) = self[pos]
-        {
-            if piece_colour == colour && matches!(shape, Shape::Flat | Shape::Capstone) {
-                seen.insert(pos);
-                for neighbor in pos.neighbors() {
-                    self.find_paths_recursive(neighbor, colour, seen)
-                }
-            }
+    /// Get the squares of a shortest road for this colour, if it has one.
+    pub fn road(&self, colour: Colour) -> Option<Bitboard<N>> {
+        let roads = self.road_pieces(colour);
+        [
+            (Bitboard::column(0), Bitboard::column(N - 1)),
+            (Bitboard::row(0), Bitboard::row(N - 1)),
+        ]
+        .into_iter()
+        .filter_map(|(from, to)| shortest_path(from & roads, to, roads))
+        .min_by_key(|road| road.count())
+    }
+}
+
+/// Breadth first search from `from` to `to` through `within`,
+/// one bitboard per step so nothing is allocated.
+fn shortest_path<const N: usize>(
+    from: Bitboard<N>,
+    to: Bitboard<N>,
+    within: Bitboard<N>,
+) -> Option<Bitboard<N>> {
+    let mut layers = ArrayVec::<Bitboard<N>, 64>::new();
+    let mut seen = from;
+    let mut layer = from;
+    while (layer & to).is_empty() {
+        if layer.is_empty() {
+            return None;
         }
+        layers.push(layer);
+        layer = layer.dilate() & within & !seen;
+        seen = seen | layer;
     }
+
+    // walk back through the layers picking a neighbor each time
+    let mut pos = (layer & to).positions().next()?;
+    let mut path = Bitboard::default().with(pos);
+    for &layer in layers.iter().rev() {
+        pos = (layer & Bitboard::default().with(pos).dilate())
+            .positions()
+            .next()?;
+        path = path.with(pos);
+    }
+    Some(path)
 }
 
-impl<const N: usize> Default for Board<N>
-where
-    [[Option<Tile>; N]; N]: Default,
-{
+impl<const N: usize> Default for Board<N> {
     fn default() -> Self {
         Self {
-            data: <[[Option<Tile>; N]; N]>::default(),
+            data: [[None; N]; N],
+            white: Bitboard::default(),
+            black: Bitboard::default(),
+            walls: Bitboard::default(),
+            caps: Bitboard::default(),
+            hash: 0,
         }
     }
 }
@@ -99,12 +195,6 @@ impl<const N: usize> Index<Pos<N>> for Board<N> {
     }
 }
 
-impl<const N: usize> IndexMut<Pos<N>> for Board<N> {
-    fn index_mut(&mut self, index: Pos<N>) -> &mut Self::Output {
-        self.data.index_mut(index.y).index_mut(index.x)
-    }
-}
-
 impl<const N: usize> Display for Board<N> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         let mut output = String::new();
@@ -148,3 +238,72 @@ impl<const N: usize> Display for Board<N> {
         write!(f, "{}", output)
     }
 }
+
+impl<const N: usize> Board<N> {
+    /// Show the whole stack on each square rather than just its top, see
+    /// [`DetailedBoard`].
+    pub fn detailed(&self) -> DetailedBoard<'_, N> {
+        DetailedBoard(self)
+    }
+}
+
+/// Displays a board with every piece of each stack. A stack is written
+/// from the bottom up with `w` and `b` for the flats below the top, then
+/// the top piece like the normal display, so `wb/w` is a white wall on a
+/// black flat on a white flat.
+pub struct DetailedBoard<'a, const N: usize>(&'a Board<N>);
+
+impl<const N: usize> Display for DetailedBoard<'_, N> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let squares: Vec<Vec<String>> = (0..N)
+            .map(|y| {
+                (0..N)
+                    .map(|x| match self.0[Pos { x, y }] {
+                        Some(tile) => {
+                            let mut square: String = tile
+                                .stack
+                                .iter()
+                                .map(|colour| match colour {
+                                    Colour::White => 'w',
+                                    Colour::Black => 'b',
+                                })
+                                .collect();
+                            square.push(match tile.top.shape {
+                                Shape::Flat => '_',
+                                Shape::Wall => '/',
+                                Shape::Capstone => 'o',
+                            });
+                            square.push(match tile.top.colour {
+                                Colour::White => 'w',
+                                Colour::Black => 'b',
+                            });
+                            square
+                        }
+                        None => ".".to_string(),
+                    })
+                    .collect()
+            })
+            .collect();
+        let width = squares
+            .iter()
+            .flatten()
+            .map(String::len)
+            .max()
+            .unwrap_or(0)
+            .max(2);
+
+        // header with letters, then each row starting with its number
+        let mut lines = vec![(0..N).fold(" ".to_string(), |line, x| {
+            format!("{line} {:width$}", (b'a' + x as u8) as char)
+        })];
+        for (y, row) in squares.iter().enumerate() {
+            lines.push(row.iter().fold((y + 1).to_string(), |line, square| {
+                format!("{line} {square:width$}")
+            }));
+        }
+        for line in lines {
+            writeln!(f, "{}", line.trim_end())?;
+        }
+        Ok(())
+    }
+}
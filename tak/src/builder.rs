@@ -0,0 +1,260 @@
+use std::fmt::Display;
+
+use crate::{
+    board::Board,
+    colour::Colour,
+    game::{Capstones, Game, Rules, Stones},
+    komi::Komi,
+    pos::Pos,
+    ptn::ToPTN,
+    tile::{Piece, Shape, Stack, Tile},
+};
+
+/// Why a [`GameBuilder`] could not set up a game.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum SetupError {
+    /// A wall or capstone is below the top of the stack on this square.
+    BuriedPiece { square: String },
+    /// The stack on this square is too tall to store.
+    StackTooTall { square: String },
+    /// There are more pieces of this colour on the board than the rules
+    /// start with.
+    TooManyPieces { colour: Colour },
+    /// The reserves given for this colour and its pieces on the board do
+    /// not add up to the starting counts of the rules.
+    ReservesMismatch { colour: Colour },
+    /// The player to move does not match the ply.
+    PlyParity { ply: u64, to_move: Colour },
+    /// The board cannot come from the opening swap at this ply, where only
+    /// Black's flats have been placed, one per ply.
+    OpeningPosition { ply: u64 },
+    /// No game can be played with the rules, see [`Rules::check`].
+    InvalidRules,
+}
+
+impl Display for SetupError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SetupError::BuriedPiece { square } => {
+                write!(f, "only flats can be below the top of the stack on {square}")
+            }
+            SetupError::StackTooTall { square } => write!(f, "the stack on {square} is too tall"),
+            SetupError::TooManyPieces { colour } => {
+                write!(f, "{colour} has more pieces on the board than the rules allow")
+            }
+            SetupError::ReservesMismatch { colour } => write!(
+                f,
+                "the reserves and pieces on the board of {colour} do not add up to the rules"
+            ),
+            SetupError::PlyParity { ply, to_move } => write!(f, "{to_move} cannot be to move at ply {ply}"),
+            SetupError::OpeningPosition { ply } => {
+                write!(f, "the board cannot come from the opening at ply {ply}")
+            }
+            SetupError::InvalidRules => write!(f, "no game can be played with these rules"),
+        }
+    }
+}
+
+impl std::error::Error for SetupError {}
+
+/// Sets up a position piece by piece, checking that it makes sense before
+/// it becomes a [`Game`].
+///
+/// Reserves are taken from the rules minus the pieces on the board, unless
+/// they are given, and the ply is the first one with the right player to
+/// move after the pieces could have been placed, unless it is given.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct GameBuilder<const N: usize> {
+    /// Pieces on each square from the bottom up, indexed by `y * N + x`.
+    squares: Vec<Vec<Piece>>,
+    rules: Rules,
+    to_move: Colour,
+    ply: Option<u64>,
+    reserves: [Option<(Stones, Capstones)>; 2],
+}
+
+impl<const N: usize> Default for GameBuilder<N> {
+    fn default() -> Self {
+        Self::with_rules(Game::<N>::standard_rules())
+    }
+}
+
+impl<const N: usize> GameBuilder<N> {
+    /// Start from an empty board with the standard rules for this size.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Start from an empty board.
+    pub fn with_rules(rules: Rules) -> Self {
+        GameBuilder {
+            squares: vec![Vec::new(); N * N],
+            rules,
+            to_move: Colour::White,
+            ply: None,
+            reserves: [None; 2],
+        }
+    }
+
+    /// Start from the position of a game, keeping its ply and reserves.
+    pub fn from_game(game: &Game<N>) -> Self {
+        let mut builder = Self::with_rules(game.rules);
+        for y in 0..N {
+            for x in 0..N {
+                let pos = Pos { x, y };
+                if let Some(tile) = game.board[pos] {
+                    let stack = tile.stack.iter().map(|colour| Piece {
+                        colour,
+                        shape: Shape::Flat,
+                    });
+                    builder.set_stack(pos, stack.chain([tile.top]));
+                }
+            }
+        }
+        builder.to_move(game.to_move).ply(game.ply);
+        builder.reserves(Colour::White, game.white_stones, game.white_caps);
+        builder.reserves(Colour::Black, game.black_stones, game.black_caps);
+        builder
+    }
+
+    /// Replace the stack on a square, listed from the bottom up.
+    pub fn set_stack(&mut self, pos: Pos<N>, pieces: impl IntoIterator<Item = Piece>) -> &mut Self {
+        self.squares[pos.y * N + pos.x] = pieces.into_iter().collect();
+        self
+    }
+
+    /// Put a piece on top of the stack on a square.
+    pub fn place(&mut self, pos: Pos<N>, piece: Piece) -> &mut Self {
+        self.squares[pos.y * N + pos.x].push(piece);
+        self
+    }
+
+    /// Take the top piece off a square.
+    pub fn remove(&mut self, pos: Pos<N>) -> Option<Piece> {
+        self.squares[pos.y * N + pos.x].pop()
+    }
+
+    /// Remove every piece from a square.
+    pub fn clear(&mut self, pos: Pos<N>) -> &mut Self {
+        self.squares[pos.y * N + pos.x].clear();
+        self
+    }
+
+    pub fn rules(&mut self, rules: Rules) -> &mut Self {
+        self.rules = rules;
+        self
+    }
+
+    pub fn komi(&mut self, komi: impl Into<Komi>) -> &mut Self {
+        self.rules.komi = komi.into();
+        self
+    }
+
+    pub fn to_move(&mut self, colour: Colour) -> &mut Self {
+        self.to_move = colour;
+        self
+    }
+
+    /// Set the ply, or go back to working it out from the board with `None`.
+    pub fn ply(&mut self, ply: impl Into<Option<u64>>) -> &mut Self {
+        self.ply = ply.into();
+        self
+    }
+
+    /// Set the reserves of a colour instead of working them out from the
+    /// board.
+    pub fn reserves(&mut self, colour: Colour, stones: Stones, caps: Capstones) -> &mut Self {
+        self.reserves[colour as usize] = Some((stones, caps));
+        self
+    }
+
+    /// Go back to working out the reserves from the board.
+    pub fn derive_reserves(&mut self) -> &mut Self {
+        self.reserves = [None; 2];
+        self
+    }
+
+    /// Check the setup and make the game.
+    pub fn build(&self) -> Result<Game<N>, SetupError> {
+        if self.rules.check().is_err() {
+            return Err(SetupError::InvalidRules);
+        }
+        let mut board = Board::default();
+        for (i, pieces) in self.squares.iter().enumerate() {
+            let pos = Pos { x: i % N, y: i / N };
+            let square = || pos.to_ptn();
+            if let Some((&top, below)) = pieces.split_last() {
+                if below.iter().any(|piece| piece.shape != Shape::Flat) {
+                    return Err(SetupError::BuriedPiece { square: square() });
+                }
+                if below.len() > Stack::CAPACITY {
+                    return Err(SetupError::StackTooTall { square: square() });
+                }
+                let stack: Stack = below.iter().map(|piece| piece.colour).collect();
+                board.set(pos, Some(Tile { top, stack }));
+            }
+        }
+
+        let mut reserves = [(0, 0); 2];
+        for colour in [Colour::White, Colour::Black] {
+            let (stones, caps) = board.piece_counts(colour);
+            reserves[colour as usize] = match self.reserves[colour as usize] {
+                Some((stones_left, caps_left)) => {
+                    if stones + stones_left as usize != self.rules.stones as usize
+                        || caps + caps_left as usize != self.rules.caps as usize
+                    {
+                        return Err(SetupError::ReservesMismatch { colour });
+                    }
+                    (stones_left, caps_left)
+                }
+                None => {
+                    let stones_left = (self.rules.stones as usize).checked_sub(stones);
+                    let caps_left = (self.rules.caps as usize).checked_sub(caps);
+                    match stones_left.zip(caps_left) {
+                        Some((stones, caps)) => (stones as Stones, caps as Capstones),
+                        None => return Err(SetupError::TooManyPieces { colour }),
+                    }
+                }
+            };
+        }
+        let pieces: usize = self.squares.iter().map(Vec::len).sum();
+
+        let first = match self.to_move {
+            Colour::White => 0,
+            Colour::Black => 1,
+        };
+        let ply = self.ply.unwrap_or_else(|| {
+            // the first ply with this player to move after the opening
+            let placed = pieces.min(2) as u64;
+            placed + (placed + first) % 2
+        });
+        if ply % 2 != first {
+            return Err(SetupError::PlyParity {
+                ply,
+                to_move: self.to_move,
+            });
+        }
+        let black_flat = Piece {
+            colour: Colour::Black,
+            shape: Shape::Flat,
+        };
+        if self.rules.swap
+            && ply < 2
+            && (pieces as u64 != ply || self.squares.iter().flatten().any(|&piece| piece != black_flat))
+        {
+            return Err(SetupError::OpeningPosition { ply });
+        }
+
+        let [(white_stones, white_caps), (black_stones, black_caps)] = reserves;
+        Ok(Game {
+            board,
+            to_move: self.to_move,
+            ply,
+            white_stones,
+            black_stones,
+            white_caps,
+            black_caps,
+            rules: self.rules,
+        })
+    }
+}
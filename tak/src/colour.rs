@@ -1,6 +1,10 @@
 use std::{fmt::Display, str::FromStr};
 
-#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+use crate::error::Error;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "lowercase"))]
 pub enum Colour {
     White,
     Black,
@@ -17,7 +21,7 @@ impl Colour {
 }
 
 impl FromStr for Colour {
-    type Err = String;
+    type Err = Error;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         let lower = s.to_lowercase();
@@ -26,7 +30,7 @@ impl FromStr for Colour {
         } else if lower == "b" || lower == "black" {
             Ok(Colour::Black)
         } else {
-            Err(format!("could not convert {s} to colour"))
+            Err(Error::parse(s, 0, "could not convert to colour"))
         }
     }
 }
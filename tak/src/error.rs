@@ -0,0 +1,120 @@
+use std::fmt::Display;
+
+use crate::game::{Capstones, Stones};
+
+/// Everything that can go wrong in the tak crate.
+/// Squares are given in PTN, like `c3`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Error {
+    /// A piece was placed on a square that is not empty.
+    OccupiedSquare { square: String },
+    /// A capstone was placed without any capstones in reserve.
+    NoCapstoneLeft,
+    /// A flat or wall was placed without any stones in reserve.
+    NoStonesLeft,
+    /// A wall or capstone was placed on one of the first two plies.
+    OpeningPlacement { ply: u64 },
+    /// Something other than a lone capstone was dropped on a wall.
+    CannotFlattenWall,
+    /// A piece was dropped on a capstone.
+    CannotStackOnCapstone,
+    /// More pieces were picked up than the carry limit allows.
+    CarryLimitExceeded { amount: usize, limit: usize },
+    /// More pieces were picked up than there are in the stack.
+    StackTooSmall { amount: usize, size: usize },
+    /// A spread tried to pick up no pieces.
+    EmptyCarry,
+    /// A spread went past the edge of the board.
+    MoveOffBoard { square: String },
+    /// A spread started on an empty square.
+    EmptySquare { square: String },
+    /// A spread started on a stack controlled by the opponent.
+    NotYourStack { square: String },
+    /// The squares are the same or diagonal to each other.
+    NoDirection { from: String, to: String },
+    /// An opening was played on a game that already started.
+    GameStarted,
+    /// A game cannot be played with these piece counts, because there
+    /// are no stones for the opening or a stack could grow too tall.
+    InvalidRules { stones: Stones, caps: Capstones },
+    /// The text could not be parsed,
+    /// `position` is the byte offset of the problem in `input`.
+    ParseError {
+        input: String,
+        position: usize,
+        message: String,
+    },
+    /// Reading the input failed.
+    Io { message: String },
+}
+
+pub type TakResult<T> = Result<T, Error>;
+
+impl Error {
+    pub(crate) fn parse(input: &str, position: usize, message: impl Into<String>) -> Self {
+        Error::ParseError {
+            input: input.to_string(),
+            position,
+            message: message.into(),
+        }
+    }
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::OccupiedSquare { square } => write!(f, "cannot place a piece on occupied square {square}"),
+            Error::NoCapstoneLeft => write!(f, "there is no capstone left to play"),
+            Error::NoStonesLeft => write!(f, "there are no stones left to play"),
+            Error::OpeningPlacement { ply } => {
+                write!(
+                    f,
+                    "cannot play a wall or capstone on the first two plies, ply={ply}"
+                )
+            }
+            Error::CannotFlattenWall => write!(f, "can only flatten a wall with a capstone"),
+            Error::CannotStackOnCapstone => write!(f, "cannot create a stack on top of a capstone"),
+            Error::CarryLimitExceeded { amount, limit } => {
+                write!(
+                    f,
+                    "cannot take more than the carry limit, amount={amount}, limit={limit}"
+                )
+            }
+            Error::StackTooSmall { amount, size } => write!(
+                f,
+                "cannot take more pieces than there are on the tile, amount={amount}, size={size}"
+            ),
+            Error::EmptyCarry => write!(f, "cannot take 0 from a tile"),
+            Error::MoveOffBoard { square } => write!(f, "cannot move out of the board from {square}"),
+            Error::EmptySquare { square } => write!(f, "cannot move from empty square {square}"),
+            Error::NotYourStack { square } => write!(f, "cannot move stack {square} that you do not own"),
+            Error::NoDirection { from, to } => write!(f, "there is no direction from {from} to {to}"),
+            Error::GameStarted => {
+                write!(
+                    f,
+                    "openings should be played on an empty board with no previous plies"
+                )
+            }
+            Error::InvalidRules { stones, caps } => write!(
+                f,
+                "cannot play with {stones} stones and {caps} capstones per player"
+            ),
+            Error::ParseError {
+                input,
+                position,
+                message,
+            } => write!(f, "{message} at position {position} of {input:?}"),
+            Error::Io { message } => write!(f, "could not read input: {message}"),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl From<std::io::Error> for Error {
+    fn from(err: std::io::Error) -> Self {
+        Error::Io {
+            message: err.to_string(),
+        }
+    }
+}
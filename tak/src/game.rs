@@ -6,36 +6,88 @@ use crate::{
     board::Board,
     colour::Colour,
     direction::Direction,
+    error::{Error, TakResult},
+    komi::Komi,
     pos::Pos,
-    tile::{Piece, Shape, Tile},
+    ptn::ToPTN,
+    tile::{Piece, Shape, Stack, Tile},
     turn::Turn,
-    StrResult,
+    zobrist::{state_hash, Zobrist},
 };
 
-type Stones = u8;
-type Capstones = u8;
+pub(crate) type Stones = u8;
+pub(crate) type Capstones = u8;
 pub const fn default_starting_stones(width: usize) -> (Stones, Capstones) {
-    match width {
-        3 => (10, 0),
-        4 => (15, 0),
-        5 => (21, 1),
-        6 => (30, 1),
-        7 => (40, 2),
-        8 => (50, 2),
-        _ => panic!("missing starting stones for non-standard board size"),
+    match Rules::standard(width) {
+        Some(rules) => (rules.stones, rules.caps),
+        None => panic!("missing starting stones for non-standard board size"),
     }
 }
 
-const TURN_LIMIT: u64 = 400;
+/// The rule variant a game is played with.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Rules {
+    /// Stones each player starts with, which can be played as flats or
+    /// walls.
+    pub stones: Stones,
+    /// Capstones each player starts with.
+    pub caps: Capstones,
+    /// Number of plies after which the game is a draw, if any.
+    pub turn_limit: Option<u64>,
+    /// Whether both players place a flat of the opponent on their first
+    /// turn.
+    pub swap: bool,
+    /// Flats added to Black's count when the game ends on flats.
+    pub komi: Komi,
+}
+
+impl Rules {
+    /// The usual rules for a board size, or `None` if the size has no
+    /// standard piece counts.
+    pub const fn standard(width: usize) -> Option<Self> {
+        let (stones, caps) = match width {
+            3 => (10, 0),
+            4 => (15, 0),
+            5 => (21, 1),
+            6 => (30, 1),
+            7 => (40, 2),
+            8 => (50, 2),
+            _ => return None,
+        };
+        Some(Rules {
+            stones,
+            caps,
+            turn_limit: Some(400),
+            swap: true,
+            komi: Komi::from_half_points(0),
+        })
+    }
+
+    /// Check that a game can be played with these rules. There has to be
+    /// a stone for the opening, and all pieces have to fit in one stack.
+    pub fn check(&self) -> TakResult<()> {
+        let pieces = 2 * (self.stones as usize + self.caps as usize);
+        if self.stones == 0 || pieces > Stack::CAPACITY + 1 {
+            return Err(Error::InvalidRules {
+                stones: self.stones,
+                caps: self.caps,
+            });
+        }
+        Ok(())
+    }
+}
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
 pub enum GameResult {
     Winner { colour: Colour, road: bool },
     Draw { turn_limit: bool },
     Ongoing,
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq, Eq)]
 pub struct Game<const N: usize> {
     pub board: Board<N>,
     pub to_move: Colour,
@@ -44,43 +96,69 @@ pub struct Game<const N: usize> {
     pub black_stones: Stones,
     pub white_caps: Capstones,
     pub black_caps: Capstones,
-    pub komi: i32,
+    pub rules: Rules,
 }
 
-impl<const N: usize> Game<N>
-where
-    [[Option<Tile>; N]; N]: Default,
-{
-    pub fn with_komi(komi: i32) -> Self {
-        Game {
-            komi,
-            ..Default::default()
-        }
+/// Record of a played turn, see [`Game::play_undoable`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Undo<const N: usize> {
+    turn: Turn<N>,
+    flattened: bool,
+}
+
+impl<const N: usize> Undo<N> {
+    pub fn turn(&self) -> &Turn<N> {
+        &self.turn
     }
 }
 
-impl<const N: usize> Default for Game<N>
-where
-    [[Option<Tile>; N]; N]: Default,
-{
-    fn default() -> Self {
-        let (stones, capstones) = default_starting_stones(N);
+impl<const N: usize> Game<N> {
+    /// Start a game with the standard rules for this size and some komi.
+    pub fn with_komi(komi: impl Into<Komi>) -> Self {
+        Self::start(Rules {
+            komi: komi.into(),
+            ..Self::standard_rules()
+        })
+    }
+
+    /// Start a game with full reserves for these rules, if they can be
+    /// played, see [`Rules::check`].
+    pub fn with_rules(rules: Rules) -> TakResult<Self> {
+        rules.check()?;
+        Ok(Self::start(rules))
+    }
+
+    /// Start a game with rules known to be valid.
+    fn start(rules: Rules) -> Self {
         Self {
             board: Board::default(),
             to_move: Colour::White, // White picks the first move for Black
             ply: 0,
-            white_stones: stones,
-            black_stones: stones,
-            white_caps: capstones,
-            black_caps: capstones,
-            komi: 0,
+            white_stones: rules.stones,
+            black_stones: rules.stones,
+            white_caps: rules.caps,
+            black_caps: rules.caps,
+            rules,
         }
     }
+
+    /// The standard rules for this size, see [`Rules::standard`].
+    /// Panics for sizes without them, which need [`Game::with_rules`].
+    pub(crate) fn standard_rules() -> Rules {
+        Rules::standard(N).expect("missing standard rules for non-standard board size")
+    }
+}
+
+impl<const N: usize> Default for Game<N> {
+    fn default() -> Self {
+        Self::start(Self::standard_rules())
+    }
 }
 
 impl<const N: usize> Game<N> {
+    /// Whether the player to move places a flat of the opponent.
     pub fn swap(&self) -> bool {
-        self.ply < 2
+        self.rules.swap && self.ply < 2
     }
 
     pub fn colour(&self) -> Colour {
@@ -91,9 +169,9 @@ impl<const N: usize> Game<N> {
         }
     }
 
-    pub fn opening(&mut self, opening_index: usize) -> StrResult<Vec<Turn<N>>> {
+    pub fn opening(&mut self, opening_index: usize) -> TakResult<Vec<Turn<N>>> {
         if !self.board.empty() || self.ply != 0 {
-            return Err("openings should be played on an empty board with no previous plies".to_string());
+            return Err(Error::GameStarted);
         }
         let i = opening_index % (N * N * (N * N - 1));
         let first = self.possible_turns().into_iter().nth(i / (N * N - 1)).unwrap();
@@ -104,14 +182,14 @@ impl<const N: usize> Game<N> {
     }
 
     /// Play the nth possible turn. Useful for random openings.
-    pub fn nth_move(&mut self, mut n: usize) -> StrResult<()> {
+    pub fn nth_move(&mut self, mut n: usize) -> TakResult<()> {
         let turns = self.possible_turns();
         n %= turns.len();
         self.play(turns.into_iter().nth(n).unwrap())
     }
 
     /// Like nth_move except limited to only placing flats.
-    pub fn nth_place_flat(&mut self, mut n: usize) -> StrResult<()> {
+    pub fn nth_place_flat(&mut self, mut n: usize) -> TakResult<()> {
         let turns: Vec<_> = self
             .possible_turns()
             .into_iter()
@@ -126,108 +204,205 @@ impl<const N: usize> Game<N> {
         self.play(turns.into_iter().nth(n).unwrap())
     }
 
+    /// Zobrist key of the position.
+    /// Covers the board, the player to move, the reserves and the komi,
+    /// but not the ply, nor stack pieces deeper than
+    /// [`ZOBRIST_STACK_DEPTH`](crate::ZOBRIST_STACK_DEPTH), see
+    /// [`Board::hash`].
+    pub fn hash(&self) -> u64 {
+        self.board.hash() ^ self.state_hash()
+    }
+
+    /// Compute the Zobrist key from scratch, should always equal
+    /// [`Game::hash`].
+    pub fn compute_hash(&self) -> u64 {
+        Zobrist::default().game(self)
+    }
+
+    pub(crate) fn state_hash(&self) -> u64 {
+        state_hash(
+            self.to_move,
+            (self.white_stones, self.white_caps),
+            (self.black_stones, self.black_caps),
+            self.rules.komi.half_points(),
+        )
+    }
+
     pub fn get_counts(&self) -> (Stones, Capstones) {
-        match self.to_move {
-            Colour::White => (self.white_stones, self.white_caps),
-            Colour::Black => (self.black_stones, self.black_caps),
-        }
+        self.reserves(self.to_move)
     }
 
-    fn dec_stones(&mut self) {
-        match self.to_move {
-            Colour::White => self.white_stones -= 1,
-            Colour::Black => self.black_stones -= 1,
+    pub(crate) fn reserves(&self, colour: Colour) -> (Stones, Capstones) {
+        match colour {
+            Colour::White => (self.white_stones, self.white_caps),
+            Colour::Black => (self.black_stones, self.black_caps),
         }
     }
 
-    fn dec_caps(&mut self) {
-        match self.to_move {
-            Colour::White => self.white_caps -= 1,
-            Colour::Black => self.black_caps -= 1,
+    /// The reserves the next piece is placed from, which belong to the
+    /// opponent during the swap.
+    fn reserves_mut(&mut self) -> (&mut Stones, &mut Capstones) {
+        match self.colour() {
+            Colour::White => (&mut self.white_stones, &mut self.white_caps),
+            Colour::Black => (&mut self.black_stones, &mut self.black_caps),
         }
     }
 
-    fn execute_place(&mut self, pos: Pos<N>, shape: Shape) -> StrResult<()> {
-        let (stones, caps) = self.get_counts();
+    fn execute_place(&mut self, pos: Pos<N>, shape: Shape) -> TakResult<()> {
+        let (stones, caps) = self.reserves(self.colour());
         if self.board[pos].is_some() {
-            Err(format!(
-                "cannot place a piece in that position because it is already occupied, pos={pos:?},\n{}",
-                self.board
-            ))
+            Err(Error::OccupiedSquare { square: pos.to_ptn() })
         } else if matches!(shape, Shape::Capstone) && (caps == 0) {
-            Err(format!(
-                "there is no capstone to play, white=({}, {}), black=({}, {})",
-                self.white_stones, self.white_caps, self.black_stones, self.black_caps
-            ))
+            Err(Error::NoCapstoneLeft)
         } else if matches!(shape, Shape::Flat | Shape::Wall) && stones == 0 {
-            Err(format!(
-                "cannot play a stone without stones, white=({}, {}), black=({}, {})",
-                self.white_stones, self.white_caps, self.black_stones, self.black_caps
-            ))
-        } else if self.ply < 2 && matches!(shape, Shape::Wall | Shape::Capstone) {
-            Err(format!(
-                "cannot play a wall or capstone on the first two plies, ply={}",
-                self.ply
-            ))
+            Err(Error::NoStonesLeft)
+        } else if self.swap() && matches!(shape, Shape::Wall | Shape::Capstone) {
+            Err(Error::OpeningPlacement { ply: self.ply })
         } else {
-            self.board[pos] = Some(Tile::new(Piece {
-                colour: self.colour(),
-                shape,
-            }));
+            self.board.set(
+                pos,
+                Some(Tile::new(Piece {
+                    colour: self.colour(),
+                    shape,
+                })),
+            );
+            let (stones, caps) = self.reserves_mut();
             if matches!(shape, Shape::Flat | Shape::Wall) {
-                self.dec_stones();
+                *stones -= 1;
             } else {
-                self.dec_caps();
+                *caps -= 1;
             }
             Ok(())
         }
     }
 
-    fn execute_move(&mut self, pos: Pos<N>, direction: Direction, moves: ArrayVec<bool, N>) -> StrResult<()> {
+    /// Returns whether a wall was flattened by the move.
+    fn execute_move(
+        &mut self,
+        pos: Pos<N>,
+        direction: Direction,
+        moves: &ArrayVec<bool, N>,
+    ) -> TakResult<bool> {
         // take the pieces
-        let on_square = self.board[pos].take().ok_or("cannot move from an empty square")?;
+        let on_square = self.board[pos].ok_or_else(|| Error::EmptySquare { square: pos.to_ptn() })?;
         if on_square.top.colour != self.to_move {
-            return Err(format!(
-                "cannot move a stack that you do not own, pos={pos:?},\n{}",
-                self.board
-            ));
+            return Err(Error::NotYourStack { square: pos.to_ptn() });
         }
         let (left, carry) = on_square.take::<N>(moves.len())?;
-        self.board[pos] = left;
 
+        // build the new tiles first, so an illegal spread leaves the board as it was
+        let mut drops = ArrayVec::<(Pos<N>, Tile), N>::new();
+        let mut flattened = false;
         let mut next = pos.step(direction);
-        for (carry, &should_step) in carry.into_iter().rev().zip(&moves) {
+        for (carry, &should_step) in carry.into_iter().rev().zip(moves) {
             // only unwrap the position when it is needed
-            let p = next.ok_or(format!(
-                "cannot move out of board, pos={pos:?}, direction={direction:?}, moves={moves:?}"
-            ))?;
+            let p = next.ok_or_else(|| Error::MoveOffBoard { square: pos.to_ptn() })?;
 
             // stack the dropped piece on top
-            if let Some(t) = self.board[p].take() {
-                self.board[p] = Some(t.stack(carry)?);
+            let below = if drops.last().map(|&(last, _)| last) == Some(p) {
+                drops.pop().map(|(_, tile)| tile)
             } else {
-                self.board[p] = Some(Tile::new(carry));
-            }
+                self.board[p]
+            };
+            let tile = match below {
+                Some(t) => {
+                    flattened = matches!(t.top.shape, Shape::Wall);
+                    t.stack(carry)?
+                }
+                None => Tile::new(carry),
+            };
+            drops.push((p, tile));
             if should_step {
                 next = p.step(direction);
             }
         }
 
-        Ok(())
+        self.board.set(pos, left);
+        for (p, tile) in drops {
+            self.board.set(p, Some(tile));
+        }
+        Ok(flattened)
+    }
+
+    pub fn play(&mut self, my_move: Turn<N>) -> TakResult<()> {
+        self.play_undoable(my_move).map(|_| ())
     }
 
-    pub fn play(&mut self, my_move: Turn<N>) -> StrResult<()> {
-        match my_move {
-            Turn::Place { pos, shape } => self.execute_place(pos, shape),
+    /// Play the turn and return what is needed to take it back with
+    /// [`Game::undo`].
+    pub fn play_undoable(&mut self, my_move: Turn<N>) -> TakResult<Undo<N>> {
+        let flattened = match &my_move {
+            Turn::Place { pos, shape } => self.execute_place(*pos, *shape).map(|_| false),
             Turn::Move {
                 pos,
                 direction,
                 moves,
-            } => self.execute_move(pos, direction, moves),
+            } => self.execute_move(*pos, *direction, moves),
         }?;
         self.ply += 1;
         self.to_move = self.to_move.next();
-        Ok(())
+        Ok(Undo {
+            turn: my_move,
+            flattened,
+        })
+    }
+
+    /// Take back the last turn played.
+    /// The undo record must come from the last call to
+    /// [`Game::play_undoable`] on this game.
+    pub fn undo(&mut self, undo: Undo<N>) {
+        self.ply -= 1;
+        self.to_move = self.to_move.next();
+        match undo.turn {
+            Turn::Place { pos, shape } => {
+                self.board.set(pos, None);
+                let (stones, caps) = self.reserves_mut();
+                match shape {
+                    Shape::Flat | Shape::Wall => *stones += 1,
+                    Shape::Capstone => *caps += 1,
+                }
+            }
+            Turn::Move {
+                pos,
+                direction,
+                moves,
+            } => {
+                // find where each piece was dropped
+                let mut drops = ArrayVec::<Pos<N>, N>::new();
+                let mut next = pos.step(direction);
+                for &should_step in &moves {
+                    let p = next.expect("undo record does not fit the game");
+                    drops.push(p);
+                    if should_step {
+                        next = p.step(direction);
+                    }
+                }
+
+                // pick the pieces back up, top piece first
+                let mut carry = ArrayVec::<Piece, N>::new();
+                for (i, &p) in drops.iter().enumerate().rev() {
+                    let tile = self.board[p].expect("undo record does not fit the game");
+                    let (mut left, piece) = tile.take::<1>(1).expect("a tile always has a top piece");
+                    if undo.flattened && i == drops.len() - 1 {
+                        if let Some(tile) = &mut left {
+                            tile.top.shape = Shape::Wall;
+                        }
+                    }
+                    self.board.set(p, left);
+                    carry.push(piece[0]);
+                }
+
+                // put them back where they came from
+                let mut tile = self.board[pos];
+                for piece in carry.into_iter().rev() {
+                    tile = Some(match tile {
+                        Some(t) => t.stack(piece).expect("the stack left behind has a flat on top"),
+                        None => Tile::new(piece),
+                    });
+                }
+                self.board.set(pos, tile);
+            }
+        }
     }
 
     pub fn winner(&self) -> GameResult {
@@ -246,7 +421,7 @@ impl<const N: usize> Game<N> {
             || self.board.full()
         {
             let flat_diff = self.board.flat_diff();
-            match flat_diff.cmp(&self.komi) {
+            match (2 * flat_diff).cmp(&self.rules.komi.half_points()) {
                 Ordering::Greater => GameResult::Winner {
                     colour: Colour::White,
                     road: false,
@@ -257,7 +432,7 @@ impl<const N: usize> Game<N> {
                 },
                 Ordering::Equal => GameResult::Draw { turn_limit: false },
             }
-        } else if self.ply >= TURN_LIMIT {
+        } else if matches!(self.rules.turn_limit, Some(limit) if self.ply >= limit) {
             GameResult::Draw { turn_limit: true }
         } else {
             GameResult::Ongoing
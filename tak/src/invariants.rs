@@ -0,0 +1,87 @@
+use std::fmt::Display;
+
+use crate::{bitboard::Bitboard, colour::Colour, game::Game, ptn::ToPTN, tile::Shape};
+
+/// A way in which a [`Game`] disagrees with itself, found by
+/// [`Game::check_invariants`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum InvariantViolation {
+    /// The pieces on the board and in reserve do not add up to the
+    /// starting counts of the rules.
+    PieceCount {
+        colour: Colour,
+        stones: usize,
+        caps: usize,
+    },
+    /// The player to move does not match the ply.
+    PlyParity { ply: u64, to_move: Colour },
+    /// The bitboards of the board disagree with the tile on this square.
+    Bitboards { square: String },
+    /// The Zobrist key of the board was not kept up to date.
+    StaleHash,
+}
+
+impl Display for InvariantViolation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            InvariantViolation::PieceCount { colour, stones, caps } => write!(
+                f,
+                "{colour} has {stones} stones and {caps} capstones in total, which differs from the rules"
+            ),
+            InvariantViolation::PlyParity { ply, to_move } => {
+                write!(f, "{to_move} cannot be to move at ply {ply}")
+            }
+            InvariantViolation::Bitboards { square } => {
+                write!(f, "the bitboards do not match the tile on {square}")
+            }
+            InvariantViolation::StaleHash => write!(f, "the Zobrist key of the board is out of date"),
+        }
+    }
+}
+
+impl std::error::Error for InvariantViolation {}
+
+impl<const N: usize> Game<N> {
+    /// Check that the game is consistent with itself and its rules, for
+    /// debugging and fuzzing. Pieces below the top of a stack are only
+    /// stored by colour, so a buried capstone or wall cannot happen, but
+    /// too many capstones show up in the piece count.
+    pub fn check_invariants(&self) -> Result<(), InvariantViolation> {
+        let expected = match self.ply % 2 {
+            0 => Colour::White,
+            _ => Colour::Black,
+        };
+        if self.to_move != expected {
+            return Err(InvariantViolation::PlyParity {
+                ply: self.ply,
+                to_move: self.to_move,
+            });
+        }
+
+        for colour in [Colour::White, Colour::Black] {
+            let (stones, caps) = self.board.piece_counts(colour);
+            let (stones_left, caps_left) = self.reserves(colour);
+            let (stones, caps) = (stones + stones_left as usize, caps + caps_left as usize);
+            if (stones, caps) != (self.rules.stones as usize, self.rules.caps as usize) {
+                return Err(InvariantViolation::PieceCount { colour, stones, caps });
+            }
+        }
+
+        for pos in Bitboard::<N>::full().positions() {
+            let top = self.board[pos].map(|tile| tile.top);
+            let colour = |colour| top.map(|piece| piece.colour) == Some(colour);
+            let shape = |shape| top.map(|piece| piece.shape) == Some(shape);
+            let consistent = self.board.pieces(Colour::White).contains(pos) == colour(Colour::White)
+                && self.board.pieces(Colour::Black).contains(pos) == colour(Colour::Black)
+                && self.board.walls().contains(pos) == shape(Shape::Wall)
+                && self.board.capstones().contains(pos) == shape(Shape::Capstone);
+            if !consistent {
+                return Err(InvariantViolation::Bitboards { square: pos.to_ptn() });
+            }
+        }
+        if self.board.hash() != self.board.compute_hash() {
+            return Err(InvariantViolation::StaleHash);
+        }
+        Ok(())
+    }
+}
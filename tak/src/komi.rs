@@ -0,0 +1,52 @@
+use std::{fmt::Display, str::FromStr};
+
+use crate::error::Error;
+
+/// Flats added to Black's count when the game ends on flats, stored in
+/// half-points so that a komi like `2.5` rules out draws.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Komi(i32);
+
+impl Komi {
+    pub const fn from_half_points(half_points: i32) -> Self {
+        Komi(half_points)
+    }
+
+    pub const fn half_points(self) -> i32 {
+        self.0
+    }
+}
+
+impl From<i32> for Komi {
+    /// Komi of a whole number of flats.
+    fn from(flats: i32) -> Self {
+        Komi(2 * flats)
+    }
+}
+
+impl FromStr for Komi {
+    type Err = Error;
+
+    /// Parse komi written like `2` or `2.5`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let half_points = s
+            .parse::<f64>()
+            .ok()
+            .map(|flats| flats * 2.0)
+            .filter(|half_points| half_points.fract() == 0.0 && half_points.abs() <= i32::MAX as f64)
+            .ok_or_else(|| Error::parse(s, 0, "komi should be a multiple of 0.5"))?;
+        Ok(Komi(half_points as i32))
+    }
+}
+
+impl Display for Komi {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let sign = if self.0 < 0 { "-" } else { "" };
+        let half_points = self.0.unsigned_abs();
+        write!(f, "{sign}{}", half_points / 2)?;
+        if half_points & 1 == 1 {
+            write!(f, ".5")?;
+        }
+        Ok(())
+    }
+}
@@ -3,26 +3,60 @@
 #[macro_use]
 extern crate lazy_static;
 
+mod bitboard;
 mod board;
+mod builder;
 mod colour;
 mod direction;
+mod error;
 mod game;
+mod invariants;
+mod komi;
+mod move_list;
+mod outcome;
+mod packed;
+mod perft;
+mod playtak;
 mod pos;
 mod ptn;
+mod ptn_game;
+mod ptn_reader;
+mod record;
+#[cfg(feature = "serde")]
+mod serialize;
+mod solver;
+mod svg;
 mod symm;
+mod threats;
 mod tile;
+mod tinue;
 mod tps;
 mod turn;
-
-pub type StrResult<T> = Result<T, String>;
+mod zobrist;
 
 // re-export so you can star import everything important
-pub use board::Board;
+pub use bitboard::Bitboard;
+pub use board::{Board, DetailedBoard};
+pub use builder::{GameBuilder, SetupError};
 pub use colour::Colour;
-pub use game::{default_starting_stones, Game, GameResult};
+pub use error::{Error, TakResult};
+pub use game::{default_starting_stones, Game, GameResult, Rules, Undo};
+pub use invariants::InvariantViolation;
+pub use komi::Komi;
+pub use move_list::MoveList;
+pub use outcome::{EndReason, Outcome};
+pub use packed::PackedTurn;
+pub use perft::{perft, perft_divide};
+pub use playtak::{FromPlayTak, ToPlayTak};
 pub use pos::Pos;
 pub use ptn::{FromPTN, ToPTN};
+pub use ptn_game::{PtnGame, PtnPly};
+pub use ptn_reader::{DatabaseGame, PtnReader};
+pub use record::GameRecord;
+pub use solver::{Solution, Solver};
+pub use svg::SvgOptions;
 pub use symm::Symmetry;
-pub use tile::{Piece, Shape, Tile};
+pub use tile::{Piece, Shape, Stack, Tile};
 pub use tps::{FromTPS, ToTPS};
 pub use turn::Turn;
+pub use zobrist::{Zobrist, ZOBRIST_STACK_DEPTH};
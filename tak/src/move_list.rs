@@ -0,0 +1,185 @@
+use std::{cmp::min, ops::Deref, slice};
+
+use arrayvec::ArrayVec;
+
+use crate::{
+    direction::Direction,
+    game::Game,
+    pos::Pos,
+    tile::{Piece, Shape, Tile},
+    turn::Turn,
+};
+
+/// Buffer of turns which can be filled again and again without
+/// allocating, once it has grown to fit the largest position.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct MoveList<const N: usize> {
+    turns: Vec<Turn<N>>,
+}
+
+impl<const N: usize> MoveList<N> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Remove all turns, keeping the memory.
+    pub fn clear(&mut self) {
+        self.turns.clear();
+    }
+
+    fn push(&mut self, turn: Turn<N>) {
+        self.turns.push(turn);
+    }
+}
+
+impl<const N: usize> Deref for MoveList<N> {
+    type Target = [Turn<N>];
+
+    fn deref(&self) -> &Self::Target {
+        &self.turns
+    }
+}
+
+impl<'a, const N: usize> IntoIterator for &'a MoveList<N> {
+    type IntoIter = slice::Iter<'a, Turn<N>>;
+    type Item = &'a Turn<N>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.turns.iter()
+    }
+}
+
+impl<const N: usize> From<MoveList<N>> for Vec<Turn<N>> {
+    fn from(list: MoveList<N>) -> Self {
+        list.turns
+    }
+}
+
+impl<const N: usize> Game<N> {
+    /// Add all possible turns to the list, in the order of
+    /// [`Game::possible_turns`].
+    pub fn generate_turns(&self, list: &mut MoveList<N>) {
+        for pos in squares() {
+            match &self.board[pos] {
+                Some(tile) => self.add_spreads(list, pos, tile),
+                None => self.add_placements(list, pos),
+            }
+        }
+    }
+
+    /// Add all possible placements to the list.
+    pub fn generate_placements(&self, list: &mut MoveList<N>) {
+        for pos in squares() {
+            if self.board[pos].is_none() {
+                self.add_placements(list, pos);
+            }
+        }
+    }
+
+    /// Add all possible spreads to the list.
+    pub fn generate_spreads(&self, list: &mut MoveList<N>) {
+        for pos in squares() {
+            if let Some(tile) = &self.board[pos] {
+                self.add_spreads(list, pos, tile);
+            }
+        }
+    }
+
+    /// Add the placements on this empty square.
+    fn add_placements(&self, list: &mut MoveList<N>, pos: Pos<N>) {
+        // can only place opponent's flat on the first two plies
+        if self.swap() {
+            list.push(Turn::Place {
+                pos,
+                shape: Shape::Flat,
+            });
+            return;
+        }
+
+        let (stones, caps) = self.get_counts();
+        if stones > 0 {
+            list.push(Turn::Place {
+                pos,
+                shape: Shape::Flat,
+            });
+            list.push(Turn::Place {
+                pos,
+                shape: Shape::Wall,
+            });
+        }
+        if caps > 0 {
+            list.push(Turn::Place {
+                pos,
+                shape: Shape::Capstone,
+            });
+        }
+    }
+
+    /// Add the spreads of the stack on this square.
+    fn add_spreads(&self, list: &mut MoveList<N>, pos: Pos<N>, tile: &Tile) {
+        if self.swap() || tile.top.colour != self.to_move {
+            return;
+        }
+        for neighbour in pos.neighbors() {
+            let mut spread = Spread {
+                game: self,
+                list: &mut *list,
+                pos,
+                direction: (neighbour - pos).unwrap(),
+                capstone: tile.top.shape == Shape::Capstone,
+                moves: ArrayVec::new(),
+            };
+            for carry in 1..=min(tile.size(), N) {
+                spread.add_drops(neighbour, carry - 1);
+            }
+        }
+    }
+}
+
+/// Every square, going up the columns from left to right.
+fn squares<const N: usize>() -> impl Iterator<Item = Pos<N>> {
+    (0..N).flat_map(|x| (0..N).map(move |y| Pos { x, y }))
+}
+
+/// Spreads from one square in one direction, built up one piece at a time.
+struct Spread<'a, const N: usize> {
+    game: &'a Game<N>,
+    list: &'a mut MoveList<N>,
+    pos: Pos<N>,
+    direction: Direction,
+    capstone: bool,
+    /// True means we moved one step for the next drop.
+    moves: ArrayVec<bool, N>,
+}
+
+impl<const N: usize> Spread<'_, N> {
+    /// Drop the next piece on this square, with `left` pieces after it.
+    fn add_drops(&mut self, square: Pos<N>, left: usize) {
+        #[rustfmt::skip]
+        let can_drop = match self.game.board[square] {
+            None => true,
+            Some(Tile {top: Piece {shape: Shape::Flat, ..}, ..}) => true,
+            Some(Tile {top: Piece {shape: Shape::Wall, ..}, ..}) => left == 0 && self.capstone,
+            _ => false,
+        };
+        if !can_drop {
+            return;
+        }
+
+        self.moves.push(false);
+        if left == 0 {
+            self.list.push(Turn::Move {
+                pos: self.pos,
+                direction: self.direction,
+                moves: self.moves.clone(),
+            });
+        } else {
+            self.add_drops(square, left - 1);
+            if let Some(next) = square.step(self.direction) {
+                *self.moves.last_mut().unwrap() = true;
+                self.add_drops(next, left - 1);
+            }
+        }
+        self.moves.pop();
+    }
+}
@@ -0,0 +1,77 @@
+use crate::{
+    bitboard::Bitboard,
+    colour::Colour,
+    game::{Game, GameResult},
+    komi::Komi,
+};
+
+/// How a game ended, see [`Game::outcome`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum EndReason<const N: usize> {
+    /// The winner connected opposite edges, with a shortest road on these
+    /// squares.
+    Road { squares: Bitboard<N> },
+    /// The board filled up and the flats were counted.
+    FlatsBoardFull,
+    /// A player placed their last piece and the flats were counted.
+    FlatsReservesExhausted,
+    /// The game reached the turn limit of its rules.
+    TurnLimit,
+}
+
+/// The result of a game together with how it came about.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Outcome<const N: usize> {
+    /// The same as [`Game::winner`].
+    pub result: GameResult,
+    /// `None` while the game is ongoing.
+    pub reason: Option<EndReason<N>>,
+    /// Flats on top of the board, which decide a game on flats.
+    pub white_flats: u32,
+    pub black_flats: u32,
+    pub komi: Komi,
+}
+
+impl<const N: usize> Outcome<N> {
+    /// The PTN result, like `R-0`, `0-F` or `1/2-1/2`, or `None` while the
+    /// game is ongoing.
+    pub fn result_ptn(&self) -> Option<&'static str> {
+        match self.result {
+            GameResult::Winner {
+                colour: Colour::White,
+                road,
+            } => Some(if road { "R-0" } else { "F-0" }),
+            GameResult::Winner {
+                colour: Colour::Black,
+                road,
+            } => Some(if road { "0-R" } else { "0-F" }),
+            GameResult::Draw { .. } => Some("1/2-1/2"),
+            GameResult::Ongoing => None,
+        }
+    }
+}
+
+impl<const N: usize> Game<N> {
+    /// Like [`Game::winner`], but also tells how the game ended and the
+    /// final flat count.
+    pub fn outcome(&self) -> Outcome<N> {
+        let result = self.winner();
+        let reason = match result {
+            GameResult::Ongoing => None,
+            GameResult::Winner { colour, road: true } => Some(EndReason::Road {
+                squares: self.board.road(colour).expect("a road win should have a road"),
+            }),
+            GameResult::Draw { turn_limit: true } => Some(EndReason::TurnLimit),
+            _ if self.board.full() => Some(EndReason::FlatsBoardFull),
+            _ => Some(EndReason::FlatsReservesExhausted),
+        };
+        let flats = |colour| (self.board.flats() & self.board.pieces(colour)).count();
+        Outcome {
+            result,
+            reason,
+            white_flats: flats(Colour::White),
+            black_flats: flats(Colour::Black),
+            komi: self.rules.komi,
+        }
+    }
+}
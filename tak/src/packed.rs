@@ -0,0 +1,305 @@
+use arrayvec::ArrayVec;
+
+use crate::{direction::Direction, game::default_starting_stones, pos::Pos, tile::Shape, turn::Turn};
+
+/// Largest board size which fits in the encoding.
+const MAX_SIZE: usize = 8;
+
+/// A turn packed into 16 bits.
+///
+/// The lowest 6 bits are the square, 3 bits each for `x` and `y`. The next
+/// 2 bits are the direction of a spread or the shape of a placement, and
+/// the top 8 bits are the drop pattern of a spread, which is zero for
+/// placements. The pattern has one bit per piece carried, starting from
+/// the lowest, which is set for the last piece of each drop.
+///
+/// Every packed turn also has an index below [`PackedTurn::COUNT`]. All
+/// placements come first, then all spreads, both going up the columns from
+/// left to right. The spreads from a square go in the directions -x, -y,
+/// +x and +y, then by the number of pieces carried, then by dropping as
+/// early as possible. This is the order in which [`Game::possible_turns`]
+/// generates them with tall stacks on every square.
+///
+/// [`Game::possible_turns`]: crate::Game::possible_turns
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct PackedTurn<const N: usize>(u16);
+
+impl<const N: usize> PackedTurn<N> {
+    /// Number of different turns on this board size.
+    pub const COUNT: usize = Self::PLACEMENTS + Self::SQUARE_OFFSETS[N * N];
+    /// Number of spreads in one direction with room for this many drops.
+    const LINE: [usize; MAX_SIZE] = line_counts(N);
+    /// Number of different placements on this board size.
+    const PLACEMENTS: usize = N * N * Self::SHAPES;
+    /// Placements on each square, leaving out capstones on boards without
+    /// them.
+    const SHAPES: usize = if default_starting_stones(N).1 > 0 { 3 } else { 2 };
+    /// Number of spreads from the squares before this one.
+    const SQUARE_OFFSETS: [usize; MAX_SIZE * MAX_SIZE + 1] = square_offsets(N);
+
+    /// The raw bits.
+    pub fn bits(self) -> u16 {
+        self.0
+    }
+
+    /// Check that the bits make a turn on this board size.
+    pub fn from_bits(bits: u16) -> Option<Self> {
+        let packed = PackedTurn(bits);
+        let Pos { x, y } = packed.pos();
+        if x >= N || y >= N {
+            return None;
+        }
+        let valid = match packed.pattern() {
+            0 => packed.kind() < 3,
+            pattern => {
+                let drops = pattern.count_ones() as usize;
+                packed.carry() <= N && drops <= room(packed.pos(), packed.direction())
+            }
+        };
+        valid.then_some(packed)
+    }
+
+    /// Position in the order described above, or `None` for a capstone
+    /// placement on a board size without capstones.
+    pub fn index(self) -> Option<usize> {
+        let pos = self.pos();
+        let square = pos.x * N + pos.y;
+        if self.pattern() == 0 {
+            let shape = self.kind() as usize;
+            return (shape < Self::SHAPES).then_some(square * Self::SHAPES + shape);
+        }
+
+        let direction = self.direction();
+        let mut index = Self::PLACEMENTS + Self::SQUARE_OFFSETS[square];
+        for earlier in DIRECTIONS.into_iter().take_while(|&d| d != direction) {
+            index += Self::LINE[room(pos, earlier)];
+        }
+
+        let room = room(pos, direction);
+        let pattern = self.pattern();
+        let carry = self.carry();
+        if pattern.count_ones() as usize > room {
+            return None;
+        }
+        for smaller in 1..carry {
+            index += compositions(smaller, room);
+        }
+        // patterns where this piece stays behind come first
+        let mut steps = 0;
+        for piece in 0..carry - 1 {
+            if pattern & 1 << piece != 0 {
+                index += completions(carry - 2 - piece, room - 1 - steps);
+                steps += 1;
+            }
+        }
+        Some(index)
+    }
+
+    /// Find the turn with this index, if it is below [`PackedTurn::COUNT`].
+    pub fn from_index(mut index: usize) -> Option<Self> {
+        if index < Self::PLACEMENTS {
+            let square = index / Self::SHAPES;
+            let pos = Pos {
+                x: square / N,
+                y: square % N,
+            };
+            return Some(Self::pack(pos, (index % Self::SHAPES) as u16, 0));
+        }
+        index -= Self::PLACEMENTS;
+        if index >= Self::SQUARE_OFFSETS[N * N] {
+            return None;
+        }
+
+        let square = (0..N * N).rfind(|&square| Self::SQUARE_OFFSETS[square] <= index)?;
+        index -= Self::SQUARE_OFFSETS[square];
+        let pos = Pos {
+            x: square / N,
+            y: square % N,
+        };
+        let direction = DIRECTIONS.into_iter().find(|&direction| {
+            let count = Self::LINE[room(pos, direction)];
+            let found = index < count;
+            if !found {
+                index -= count;
+            }
+            found
+        })?;
+
+        let room = room(pos, direction);
+        let carry = (1..=N).find(|&carry| {
+            let count = compositions(carry, room);
+            let found = index < count;
+            if !found {
+                index -= count;
+            }
+            found
+        })?;
+        let mut pattern = 1 << (carry - 1);
+        let mut steps = 0;
+        for piece in 0..carry - 1 {
+            let stay = completions(carry - 2 - piece, room - 1 - steps);
+            if index >= stay {
+                index -= stay;
+                pattern |= 1 << piece;
+                steps += 1;
+            }
+        }
+        Some(Self::pack(pos, direction as u16, pattern))
+    }
+
+    fn pack(pos: Pos<N>, kind: u16, pattern: u16) -> Self {
+        PackedTurn(pos.x as u16 | (pos.y as u16) << 3 | kind << 6 | pattern << 8)
+    }
+
+    fn pos(self) -> Pos<N> {
+        Pos {
+            x: (self.0 & 0b111) as usize,
+            y: (self.0 >> 3 & 0b111) as usize,
+        }
+    }
+
+    fn kind(self) -> u16 {
+        self.0 >> 6 & 0b11
+    }
+
+    fn direction(self) -> Direction {
+        DIRECTION_BITS[self.kind() as usize]
+    }
+
+    fn pattern(self) -> u16 {
+        self.0 >> 8
+    }
+
+    /// Number of pieces carried by a spread.
+    fn carry(self) -> usize {
+        16 - self.pattern().leading_zeros() as usize
+    }
+}
+
+impl<const N: usize> From<&Turn<N>> for PackedTurn<N> {
+    fn from(turn: &Turn<N>) -> Self {
+        match turn {
+            Turn::Place { pos, shape } => Self::pack(*pos, *shape as u16, 0),
+            Turn::Move {
+                pos,
+                direction,
+                moves,
+            } => {
+                let mut pattern = 1 << (moves.len() - 1);
+                for (piece, &step) in moves.iter().enumerate() {
+                    if step {
+                        pattern |= 1 << piece;
+                    }
+                }
+                Self::pack(*pos, *direction as u16, pattern)
+            }
+        }
+    }
+}
+
+impl<const N: usize> From<Turn<N>> for PackedTurn<N> {
+    fn from(turn: Turn<N>) -> Self {
+        PackedTurn::from(&turn)
+    }
+}
+
+impl<const N: usize> From<PackedTurn<N>> for Turn<N> {
+    fn from(packed: PackedTurn<N>) -> Self {
+        let pos = packed.pos();
+        match packed.pattern() {
+            0 => Turn::Place {
+                pos,
+                shape: match packed.kind() {
+                    0 => Shape::Flat,
+                    1 => Shape::Wall,
+                    _ => Shape::Capstone,
+                },
+            },
+            pattern => {
+                let mut moves = ArrayVec::new();
+                for piece in 0..packed.carry() - 1 {
+                    moves.push(pattern & 1 << piece != 0);
+                }
+                moves.push(false);
+                Turn::Move {
+                    pos,
+                    direction: packed.direction(),
+                    moves,
+                }
+            }
+        }
+    }
+}
+
+/// Order of the directions in the index.
+const DIRECTIONS: [Direction; 4] = [Direction::NegX, Direction::NegY, Direction::PosX, Direction::PosY];
+/// Directions by their bits.
+const DIRECTION_BITS: [Direction; 4] = [Direction::PosX, Direction::PosY, Direction::NegX, Direction::NegY];
+
+/// Number of squares from this one to the edge of the board.
+fn room<const N: usize>(pos: Pos<N>, direction: Direction) -> usize {
+    match direction {
+        Direction::PosX => N - 1 - pos.x,
+        Direction::PosY => N - 1 - pos.y,
+        Direction::NegX => pos.x,
+        Direction::NegY => pos.y,
+    }
+}
+
+/// Ways to choose where the remaining pieces step forward, with at most
+/// `steps` steps.
+const fn completions(pieces: usize, steps: usize) -> usize {
+    let mut count = 0;
+    let mut k = 0;
+    while k <= pieces && k <= steps {
+        count += binomial(pieces, k);
+        k += 1;
+    }
+    count
+}
+
+/// Ways to drop this many pieces on at most `room` squares.
+const fn compositions(carry: usize, room: usize) -> usize {
+    if room == 0 {
+        0
+    } else {
+        completions(carry - 1, room - 1)
+    }
+}
+
+const fn binomial(n: usize, k: usize) -> usize {
+    let mut result = 1;
+    let mut i = 0;
+    while i < k {
+        result = result * (n - i) / (i + 1);
+        i += 1;
+    }
+    result
+}
+
+const fn line_counts(n: usize) -> [usize; MAX_SIZE] {
+    let mut counts = [0; MAX_SIZE];
+    let mut room = 0;
+    while room < MAX_SIZE {
+        let mut carry = 1;
+        while carry <= n {
+            counts[room] += compositions(carry, room);
+            carry += 1;
+        }
+        room += 1;
+    }
+    counts
+}
+
+const fn square_offsets(n: usize) -> [usize; MAX_SIZE * MAX_SIZE + 1] {
+    let line = line_counts(n);
+    let mut offsets = [0; MAX_SIZE * MAX_SIZE + 1];
+    let mut square = 0;
+    while square < n * n {
+        let (x, y) = (square / n, square % n);
+        let spreads = line[x] + line[y] + line[n - 1 - x] + line[n - 1 - y];
+        offsets[square + 1] = offsets[square] + spreads;
+        square += 1;
+    }
+    offsets
+}
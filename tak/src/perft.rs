@@ -0,0 +1,132 @@
+use std::{
+    collections::HashMap,
+    sync::atomic::{AtomicUsize, Ordering},
+    thread,
+};
+
+use crate::{
+    game::{Game, GameResult},
+    move_list::MoveList,
+    turn::Turn,
+    zobrist::TableKey,
+};
+
+/// Count the positions `depth` plies from this one, which is how move
+/// generators are compared between engines. Games which end early count
+/// as a single position, like a position at depth 0. So a game which is
+/// already over counts as 1 at any depth.
+///
+/// The turns from the root are shared out between threads. With
+/// `transpositions` each thread also remembers the count of every position
+/// it has seen, which is much faster for deeper searches but takes a lot
+/// of memory.
+pub fn perft<const N: usize>(game: &Game<N>, depth: usize, transpositions: bool) -> u64 {
+    if depth == 0 || game.winner() != GameResult::Ongoing {
+        return 1;
+    }
+    perft_divide(game, depth, transpositions)
+        .into_iter()
+        .map(|(_, count)| count)
+        .sum()
+}
+
+/// Like [`perft`], but split up by the turn played first, in the order of
+/// [`Game::possible_turns`]. Empty when there is nothing to divide.
+pub fn perft_divide<const N: usize>(
+    game: &Game<N>,
+    depth: usize,
+    transpositions: bool,
+) -> Vec<(Turn<N>, u64)> {
+    if depth == 0 || game.winner() != GameResult::Ongoing {
+        return Vec::new();
+    }
+    let turns = game.possible_turns();
+    let next = AtomicUsize::new(0);
+    let threads = thread::available_parallelism()
+        .map_or(1, usize::from)
+        .min(turns.len());
+
+    let mut counts = vec![0; turns.len()];
+    thread::scope(|scope| {
+        let workers: Vec<_> = (0..threads)
+            .map(|_| {
+                scope.spawn(|| {
+                    let mut counter = Counter::new(depth, transpositions);
+                    let mut game = game.clone();
+                    let mut found = Vec::new();
+                    let mut i = next.fetch_add(1, Ordering::Relaxed);
+                    while let Some(turn) = turns.get(i) {
+                        let undo = game
+                            .play_undoable(turn.clone())
+                            .expect("possible turns should be legal");
+                        found.push((i, counter.count(&mut game, depth - 1)));
+                        game.undo(undo);
+                        i = next.fetch_add(1, Ordering::Relaxed);
+                    }
+                    found
+                })
+            })
+            .collect();
+        for worker in workers {
+            for (i, count) in worker.join().expect("perft thread panicked") {
+                counts[i] = count;
+            }
+        }
+    });
+    turns.into_iter().zip(counts).collect()
+}
+
+/// Counts positions on one thread.
+struct Counter<const N: usize> {
+    /// Counts by position and depth, if transpositions are used.
+    table: Option<HashMap<(TableKey<N>, usize), u64>>,
+    /// A list of turns for each depth, reused for every position there.
+    lists: Vec<MoveList<N>>,
+}
+
+impl<const N: usize> Counter<N> {
+    fn new(depth: usize, transpositions: bool) -> Self {
+        Self {
+            table: transpositions.then(HashMap::new),
+            lists: (0..depth).map(|_| MoveList::new()).collect(),
+        }
+    }
+
+    fn count(&mut self, game: &mut Game<N>, depth: usize) -> u64 {
+        if depth == 0 || game.winner() != GameResult::Ongoing {
+            return 1;
+        }
+        // the deeper positions get lists of their own while this one is used
+        let mut turns = std::mem::take(&mut self.lists[depth]);
+        turns.clear();
+        game.generate_turns(&mut turns);
+        let count = self.count_turns(game, depth, &turns);
+        self.lists[depth] = turns;
+        count
+    }
+
+    fn count_turns(&mut self, game: &mut Game<N>, depth: usize, turns: &MoveList<N>) -> u64 {
+        if depth == 1 {
+            return turns.len() as u64;
+        }
+
+        let key = self.table.is_some().then(|| (TableKey(game.clone()), depth));
+        if let Some((table, key)) = self.table.as_ref().zip(key.as_ref()) {
+            if let Some(&count) = table.get(key) {
+                return count;
+            }
+        }
+        let mut count = 0;
+        for turn in turns {
+            let undo = game
+                .play_undoable(turn.clone())
+                .expect("possible turns should be legal");
+            count += self.count(game, depth - 1);
+            game.undo(undo);
+        }
+        if let Some((table, key)) = self.table.as_mut().zip(key) {
+            table.insert(key, count);
+        }
+        count
+    }
+}
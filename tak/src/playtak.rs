@@ -0,0 +1,251 @@
+use arrayvec::ArrayVec;
+
+use crate::{
+    error::{Error, TakResult},
+    pos::Pos,
+    ptn::{FromPTN, ToPTN},
+    tile::Shape,
+    turn::Turn,
+};
+
+/// Notation used by the PlayTak server,
+/// like `P A1 C` for placements and `M A1 A3 2 1` for spreads.
+pub trait FromPlayTak: Sized {
+    fn from_playtak(s: &str) -> TakResult<Self>;
+}
+
+pub trait ToPlayTak {
+    fn to_playtak(&self) -> String;
+}
+
+impl<const N: usize> FromPlayTak for Pos<N> {
+    fn from_playtak(s: &str) -> TakResult<Self> {
+        if !s.starts_with(|c: char| c.is_ascii_uppercase()) {
+            return Err(Error::parse(s, 0, "expected an uppercase column letter"));
+        }
+        Pos::from_ptn(&s.to_ascii_lowercase()).map_err(|err| match err {
+            Error::ParseError {
+                position, message, ..
+            } => Error::parse(s, position, message),
+            err => err,
+        })
+    }
+}
+
+impl<const N: usize> ToPlayTak for Pos<N> {
+    fn to_playtak(&self) -> String {
+        self.to_ptn().to_ascii_uppercase()
+    }
+}
+
+impl<const N: usize> FromPlayTak for Turn<N> {
+    fn from_playtak(s: &str) -> TakResult<Self> {
+        let words: Vec<_> = s
+            .split_whitespace()
+            .map(|word| (word.as_ptr() as usize - s.as_ptr() as usize, word))
+            .collect();
+        let err = |(offset, _): (usize, &str), message: &str| Error::parse(s, offset, message);
+        let square = |(offset, word): (usize, &str)| {
+            Pos::from_playtak(word).map_err(|err| match err {
+                Error::ParseError {
+                    position, message, ..
+                } => Error::parse(s, offset + position, message),
+                err => err,
+            })
+        };
+
+        match words.as_slice() {
+            [(_, "P"), pos, rest @ ..] => {
+                let shape = match rest {
+                    [] => Shape::Flat,
+                    [(_, "W")] => Shape::Wall,
+                    [(_, "C")] => Shape::Capstone,
+                    [piece] => return Err(err(*piece, "unknown piece")),
+                    [_, extra, ..] => return Err(err(*extra, "unexpected word after the piece")),
+                };
+                Ok(Turn::Place {
+                    pos: square(*pos)?,
+                    shape,
+                })
+            }
+            [(_, "M"), from, to, drops @ ..] => {
+                let pos = square(*from)?;
+                let target = square(*to)?;
+                let direction = (target - pos).map_err(|_| err(*to, "squares are not in a line"))?;
+                let distance = pos.x.abs_diff(target.x) + pos.y.abs_diff(target.y);
+                if drops.len() != distance {
+                    return Err(err(*to, "expected one drop count for each square moved"));
+                }
+
+                let mut moves = ArrayVec::new();
+                for &drop in drops {
+                    let count = drop
+                        .1
+                        .parse::<usize>()
+                        .ok()
+                        .filter(|&count| count > 0)
+                        .ok_or_else(|| err(drop, "cannot parse drop count"))?;
+                    if moves.len() + count > N {
+                        return Err(err(drop, "carry amount is over the carry limit"));
+                    }
+                    for _ in 1..count {
+                        moves.push(false);
+                    }
+                    moves.push(true);
+                }
+                *moves.last_mut().unwrap() = false;
+
+                Ok(Turn::Move {
+                    pos,
+                    direction,
+                    moves,
+                })
+            }
+            [] => Err(Error::parse(s, 0, "empty move")),
+            [word, ..] => Err(err(*word, "expected a placement or a move")),
+        }
+    }
+}
+
+impl<const N: usize> ToPlayTak for Turn<N> {
+    fn to_playtak(&self) -> String {
+        match self {
+            Turn::Place { pos, shape } => {
+                let piece = match shape {
+                    Shape::Flat => "",
+                    Shape::Wall => " W",
+                    Shape::Capstone => " C",
+                };
+                format!("P {}{piece}", pos.to_playtak())
+            }
+            Turn::Move {
+                pos,
+                direction,
+                moves,
+            } => {
+                let drops = drop_counts(moves);
+                let target = (0..drops.len()).fold(*pos, |p, _| {
+                    p.step(*direction).expect("spread should stay on the board")
+                });
+                let drops: Vec<_> = drops.iter().map(usize::to_string).collect();
+                format!(
+                    "M {} {} {}",
+                    pos.to_playtak(),
+                    target.to_playtak(),
+                    drops.join(" ")
+                )
+            }
+        }
+    }
+}
+
+/// Number of pieces dropped on each square of a spread.
+fn drop_counts<const N: usize>(moves: &ArrayVec<bool, N>) -> ArrayVec<usize, N> {
+    let mut drops = ArrayVec::new();
+    let mut count = 0;
+    for &step in moves {
+        count += 1;
+        if step {
+            drops.push(count);
+            count = 0;
+        }
+    }
+    drops.push(count);
+    drops
+}
+
+#[cfg(feature = "takparse")]
+mod conversions {
+    use arrayvec::ArrayVec;
+    use takparse::{Direction as TpDirection, Move, MoveKind, Piece, Square};
+
+    use super::drop_counts;
+    use crate::{
+        direction::Direction,
+        error::{Error, TakResult},
+        pos::Pos,
+        tile::Shape,
+        turn::Turn,
+    };
+
+    impl<const N: usize> From<Turn<N>> for Move {
+        fn from(turn: Turn<N>) -> Self {
+            match turn {
+                Turn::Place { pos, shape } => {
+                    let piece = match shape {
+                        Shape::Flat => Piece::Flat,
+                        Shape::Wall => Piece::Wall,
+                        Shape::Capstone => Piece::Cap,
+                    };
+                    Move::new(square(pos), MoveKind::Place(piece))
+                }
+                Turn::Move {
+                    pos,
+                    direction,
+                    moves,
+                } => {
+                    let direction = match direction {
+                        Direction::PosX => TpDirection::Right,
+                        Direction::NegX => TpDirection::Left,
+                        Direction::PosY => TpDirection::Up,
+                        Direction::NegY => TpDirection::Down,
+                    };
+                    let pattern = drop_counts(&moves).into_iter().map(|drop| drop as u32).collect();
+                    Move::new(square(pos), MoveKind::Spread(direction, pattern))
+                }
+            }
+        }
+    }
+
+    impl<const N: usize> TryFrom<Move> for Turn<N> {
+        type Error = Error;
+
+        fn try_from(m: Move) -> TakResult<Self> {
+            let invalid = |message| Error::parse(&m.to_string(), 0, message);
+            let square = m.square();
+            let (x, y) = (square.column() as usize, square.row() as usize);
+            if x >= N || y >= N {
+                return Err(invalid("square is out of bounds"));
+            }
+            let pos = Pos { x, y };
+
+            Ok(match m.kind() {
+                MoveKind::Place(piece) => Turn::Place {
+                    pos,
+                    shape: match piece {
+                        Piece::Flat => Shape::Flat,
+                        Piece::Wall => Shape::Wall,
+                        Piece::Cap => Shape::Capstone,
+                    },
+                },
+                MoveKind::Spread(direction, pattern) => {
+                    if pattern.count_pieces() as usize > N {
+                        return Err(invalid("carry amount is over the carry limit"));
+                    }
+                    let mut moves = ArrayVec::new();
+                    for drop in pattern.drop_counts() {
+                        for _ in 1..drop {
+                            moves.push(false);
+                        }
+                        moves.push(true);
+                    }
+                    *moves.last_mut().unwrap() = false;
+                    Turn::Move {
+                        pos,
+                        direction: match direction {
+                            TpDirection::Right => Direction::PosX,
+                            TpDirection::Left => Direction::NegX,
+                            TpDirection::Up => Direction::PosY,
+                            TpDirection::Down => Direction::NegY,
+                        },
+                        moves,
+                    }
+                }
+            })
+        }
+    }
+
+    fn square<const N: usize>(pos: Pos<N>) -> Square {
+        Square::new(pos.x as u8, pos.y as u8)
+    }
+}
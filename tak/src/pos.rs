@@ -2,7 +2,7 @@ use std::{cmp::Ordering, ops::Sub};
 
 use arrayvec::ArrayVec;
 
-use crate::{direction::Direction, StrResult};
+use crate::{direction::Direction, error::Error, ptn::ToPTN};
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 pub struct Pos<const N: usize> {
@@ -55,29 +55,28 @@ impl<const N: usize> Pos<N> {
 }
 
 impl<const N: usize> Sub for Pos<N> {
-    type Output = StrResult<Direction>;
+    type Output = Result<Direction, Error>;
 
     fn sub(self, rhs: Self) -> Self::Output {
-        let diagonal_err = || {
-            Err(format!(
-                "cannot have a diagonal direction, from={rhs:?}, to={self:?}"
-            ))
+        let no_direction = || {
+            Err(Error::NoDirection {
+                from: rhs.to_ptn(),
+                to: self.to_ptn(),
+            })
         };
         match self.x.cmp(&rhs.x) {
             Ordering::Greater => match self.y.cmp(&rhs.y) {
                 Ordering::Equal => Ok(Direction::PosX),
-                Ordering::Less | Ordering::Greater => diagonal_err(),
+                Ordering::Less | Ordering::Greater => no_direction(),
             },
             Ordering::Less => match self.y.cmp(&rhs.y) {
                 Ordering::Equal => Ok(Direction::NegX),
-                Ordering::Less | Ordering::Greater => diagonal_err(),
+                Ordering::Less | Ordering::Greater => no_direction(),
             },
             Ordering::Equal => match self.y.cmp(&rhs.y) {
                 Ordering::Greater => Ok(Direction::PosY),
                 Ordering::Less => Ok(Direction::NegY),
-                Ordering::Equal => Err(format!(
-                    "cannot decide direction when positions are the same, lhs={self:?}, rhs={rhs:?}"
-                )),
+                Ordering::Equal => no_direction(),
             },
         }
     }
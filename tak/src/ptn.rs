@@ -1,30 +1,22 @@
 use arrayvec::ArrayVec;
-use regex::Regex;
 
 use crate::{
-    board::Board,
     colour::Colour,
     direction::Direction,
-    game::{default_starting_stones, Game},
+    error::{Error, TakResult},
+    game::Game,
     pos::Pos,
-    tile::{Shape, Tile},
-    tps::FromTPS,
+    ptn_game::PtnGame,
+    record::GameRecord,
+    tile::Shape,
     turn::Turn,
-    StrResult,
 };
 
-lazy_static! {
-    // (count)(square)(direction)(drop counts)(stone)
-    static ref TURN_MOVE_RE: Regex = Regex::new(r"([1-9]*)([a-z][1-9])([<>+-])([1-9]*)").unwrap();
-    // (stone)(square)
-    static ref TURN_PLACE_RE: Regex = Regex::new(r"([CS]?)([a-z][1-9])").unwrap();
-    static ref OPTIONS_RE: Regex = Regex::new(r#"\[(\S+) ["'](.*?)["']\]"#).unwrap();
-    static ref COMMENTS_RE: Regex = Regex::new(r"\{.*?\}").unwrap();
-    static ref PLY_SPLIT_RE: Regex = Regex::new(r"\s*\d*\. |\s+|1-0|R-0|F-0|0-1|0-R|0-F|1/2-1/2|--").unwrap();
-}
+/// Characters which can follow a turn to annotate it.
+pub(crate) const ANNOTATIONS: [char; 5] = ['\'', '"', '!', '?', '*'];
 
 pub trait FromPTN: Sized {
-    fn from_ptn(s: &str) -> StrResult<Self>;
+    fn from_ptn(s: &str) -> TakResult<Self>;
 }
 
 pub trait ToPTN {
@@ -32,13 +24,13 @@ pub trait ToPTN {
 }
 
 impl FromPTN for Direction {
-    fn from_ptn(s: &str) -> StrResult<Self> {
+    fn from_ptn(s: &str) -> TakResult<Self> {
         match s {
             "<" => Ok(Direction::NegX),
             ">" => Ok(Direction::PosX),
             "+" => Ok(Direction::PosY),
             "-" => Ok(Direction::NegY),
-            _ => Err(format!("unknown direction {s}")),
+            _ => Err(Error::parse(s, 0, "unknown direction")),
         }
     }
 }
@@ -56,17 +48,44 @@ impl ToPTN for Direction {
 }
 
 impl<const N: usize> FromPTN for Pos<N> {
-    fn from_ptn(s: &str) -> StrResult<Self> {
-        let x = (s.bytes().next().ok_or("position is too short")? - b'a') as usize;
-        let y = s[1..]
-            .parse::<usize>()
-            .map_err(|_| format!("couldn't parse vertical position {s}"))?
-            - 1;
-        if x >= N || y >= N {
-            return Err(format!("position x={x} y={y} is out of bounds"));
+    fn from_ptn(s: &str) -> TakResult<Self> {
+        let (pos, end) = parse_square(s, 0)?;
+        if end < s.len() {
+            return Err(Error::parse(s, end, "unexpected character after the square"));
         }
-        Ok(Pos { x, y })
+        Ok(pos)
+    }
+}
+
+/// Parse the square starting at byte `start` of `s`,
+/// returning it with the offset right after it.
+fn parse_square<const N: usize>(s: &str, start: usize) -> TakResult<(Pos<N>, usize)> {
+    let bytes = s.as_bytes();
+    let x = match bytes.get(start) {
+        Some(&c) if c.is_ascii_lowercase() => (c - b'a') as usize,
+        Some(_) => return Err(Error::parse(s, start, "expected a column letter")),
+        None => return Err(Error::parse(s, start, "expected a square")),
+    };
+    if x >= N {
+        return Err(Error::parse(s, start, "column is out of bounds"));
+    }
+    let end = digits_end(bytes, start + 1);
+    let y = s[start + 1..end]
+        .parse::<usize>()
+        .map_err(|_| Error::parse(s, start + 1, "expected a row number"))?;
+    if !(1..=N).contains(&y) {
+        return Err(Error::parse(s, start + 1, "row is out of bounds"));
     }
+    Ok((Pos { x, y: y - 1 }, end))
+}
+
+/// Offset of the first non-digit at or after `start`.
+fn digits_end(bytes: &[u8], start: usize) -> usize {
+    start
+        + bytes[start.min(bytes.len())..]
+            .iter()
+            .take_while(|c| c.is_ascii_digit())
+            .count()
 }
 
 impl<const N: usize> ToPTN for Pos<N> {
@@ -76,12 +95,12 @@ impl<const N: usize> ToPTN for Pos<N> {
 }
 
 impl FromPTN for Shape {
-    fn from_ptn(s: &str) -> StrResult<Self> {
+    fn from_ptn(s: &str) -> TakResult<Self> {
         match s {
             "C" => Ok(Shape::Capstone),
             "S" => Ok(Shape::Wall),
             "" => Ok(Shape::Flat),
-            _ => Err(format!("unknown shape {s}")),
+            _ => Err(Error::parse(s, 0, "unknown shape")),
         }
     }
 }
@@ -108,57 +127,108 @@ impl ToPTN for Colour {
 }
 
 impl FromPTN for Colour {
-    fn from_ptn(s: &str) -> StrResult<Self> {
+    fn from_ptn(s: &str) -> TakResult<Self> {
         match s {
             "1" => Ok(Colour::White),
             "2" => Ok(Colour::Black),
-            _ => Err(format!("unknown colour {s}")),
+            _ => Err(Error::parse(s, 0, "unknown colour")),
         }
     }
 }
 
 impl<const N: usize> FromPTN for Turn<N> {
-    fn from_ptn(s: &str) -> StrResult<Self> {
-        assert!(N < 10); // the drop notation doesn't support N >= 10
-
-        if let Some(cap) = TURN_MOVE_RE.captures(s) {
-            let carry_amount = cap[1].parse().unwrap_or(1);
-            let pos = Pos::from_ptn(&cap[2])?;
-            let direction = Direction::from_ptn(&cap[3])?;
+    /// Parse a turn like `Ca1` or `3a1>12`.
+    /// Annotations after the turn, like `'` or `?!`, are skipped.
+    fn from_ptn(s: &str) -> TakResult<Self> {
+        let bytes = s.as_bytes();
+        let err = |at, message: &str| Error::parse(s, at, message);
 
-            let mut drop_counts: Vec<_> = cap[4].chars().map(|c| c.to_digit(10).unwrap()).collect();
-            if drop_counts.is_empty() {
-                drop_counts.push(carry_amount);
-            }
-            if carry_amount != drop_counts.iter().sum() {
-                return Err(format!(
-                    "picked up {carry_amount} and tried dropping {drop_counts:?} which does not match"
-                ));
+        // carry amount or shape
+        let mut i = digits_end(bytes, 0);
+        let carry = (i > 0)
+            .then(|| {
+                s[..i]
+                    .parse::<usize>()
+                    .map_err(|_| err(0, "carry amount is too large"))
+            })
+            .transpose()?;
+        let shape = match bytes.get(i) {
+            Some(b'C') => Some(Shape::Capstone),
+            Some(b'S') => Some(Shape::Wall),
+            _ => None,
+        };
+        if shape.is_some() {
+            if carry.is_some() {
+                return Err(err(i, "cannot place a piece with a carry amount"));
             }
+            i += 1;
+        }
+
+        let (pos, end) = parse_square(s, i)?;
+        i = end;
+
+        let direction = match bytes.get(i) {
+            Some(b'<') => Some(Direction::NegX),
+            Some(b'>') => Some(Direction::PosX),
+            Some(b'+') => Some(Direction::PosY),
+            Some(b'-') => Some(Direction::NegY),
+            _ => None,
+        };
+        let turn = match direction {
+            None if carry.is_some() => return Err(err(i, "expected a direction")),
+            None => Turn::Place {
+                pos,
+                shape: shape.unwrap_or(Shape::Flat),
+            },
+            Some(_) if shape.is_some() => return Err(err(i, "cannot move a piece while placing it")),
+            Some(direction) => {
+                i += 1;
+                let carry = carry.unwrap_or(1);
+                if carry == 0 {
+                    return Err(err(0, "cannot carry no pieces"));
+                } else if carry > N {
+                    return Err(err(0, "carry amount is over the carry limit"));
+                }
+
+                // each drop is a single digit
+                let drops_start = i;
+                let mut moves = ArrayVec::new();
+                let mut dropped = 0;
+                while let Some(&c) = bytes.get(i).filter(|c| c.is_ascii_digit()) {
+                    let drop = (c - b'0') as usize;
+                    if drop == 0 {
+                        return Err(err(i, "cannot drop no pieces"));
+                    } else if dropped + drop > carry {
+                        return Err(err(i, "dropped more pieces than were picked up"));
+                    }
+                    dropped += drop;
+                    for _ in 1..drop {
+                        moves.push(false);
+                    }
+                    moves.push(true);
+                    i += 1;
+                }
+                if i == drops_start {
+                    moves.extend((1..carry).map(|_| false));
+                    moves.push(true);
+                } else if dropped < carry {
+                    return Err(err(drops_start, "dropped fewer pieces than were picked up"));
+                }
+                *moves.last_mut().unwrap() = false;
 
-            let mut moves = ArrayVec::new();
-            for drops in drop_counts {
-                for _ in 0..(drops - 1) {
-                    moves.push(false);
+                Turn::Move {
+                    pos,
+                    direction,
+                    moves,
                 }
-                moves.push(true);
             }
-            let last = moves.last_mut().unwrap();
-            *last = false;
+        };
 
-            Ok(Turn::Move {
-                pos,
-                direction,
-                moves,
-            })
-        } else {
-            let cap = TURN_PLACE_RE
-                .captures(s)
-                .ok_or_else(|| format!("didn't recognize ply {s}"))?;
-            let shape = Shape::from_ptn(&cap[1])?;
-            let pos = Pos::from_ptn(&cap[2])?;
-            Ok(Turn::Place { pos, shape })
+        let annotations_end = s.len() - s[i..].trim_start_matches(ANNOTATIONS).len();
+        if annotations_end < s.len() {
+            return Err(err(annotations_end, "unexpected character after the turn"));
         }
+        Ok(turn)
     }
 }
 
@@ -196,79 +266,26 @@ impl<const N: usize> ToPTN for Turn<N> {
     }
 }
 
-impl<const N: usize> FromPTN for Game<N>
-where
-    [[Option<Tile>; N]; N]: Default,
-{
-    fn from_ptn(s: &str) -> StrResult<Game<N>> {
-        // parse game options
-        let mut komi = 0;
-        let (mut stones, mut caps) = default_starting_stones(N);
-        let mut ply = 0;
-        let mut board = Board::default();
-        let mut to_move = Colour::White;
-        for option in OPTIONS_RE.captures_iter(s) {
-            let key = &option[1];
-            let value = &option[2];
-            match key {
-                "Komi" => komi = value.parse::<i32>().map_err(|_| "cannot parse komi")?,
-                "Flats" => stones = value.parse::<u8>().map_err(|_| "cannot parse flats")?,
-                "Caps" => caps = value.parse::<u8>().map_err(|_| "cannot parse caps")?,
-                "Size" => {
-                    if value.parse::<usize>().map_err(|_| "cannot parse size")? != N {
-                        return Err(format!("game size mismatch {value}"));
-                    }
-                }
-                "TPS" => {
-                    let mut tps = value.split_whitespace();
-                    board = Board::from_tps(tps.next().ok_or("missing board in TPS")?)?;
-                    to_move = Colour::from_ptn(tps.next().ok_or("missing who is moving in TPS")?)?;
-                    ply = (tps
-                        .next()
-                        .ok_or("missing move number in TPS")?
-                        .parse::<u64>()
-                        .map_err(|_| "cannot parse move number")?
-                        - 1)
-                        * 2
-                        + match to_move {
-                            Colour::White => 0,
-                            Colour::Black => 1,
-                        };
-                }
-                _ => {}
-            }
-        }
-
-        // remove comments
-        let s = OPTIONS_RE.replace_all(s, "");
-        let s = COMMENTS_RE.replace_all(&s, "");
+impl<const N: usize> FromPTN for GameRecord<N> {
+    fn from_ptn(s: &str) -> TakResult<GameRecord<N>> {
+        PtnGame::from_ptn(s)?.record()
+    }
+}
 
-        // get individual plies (split at move numbers, space, and game result)
-        let moves = PLY_SPLIT_RE
-            .split(&s)
-            .filter(|ss| !ss.is_empty())
-            .collect::<Vec<_>>();
+impl<const N: usize> FromPTN for Game<N> {
+    fn from_ptn(s: &str) -> TakResult<Game<N>> {
+        GameRecord::from_ptn(s).map(GameRecord::into_game)
+    }
+}
 
-        let mut game = Game {
-            komi,
-            white_stones: stones,
-            black_stones: stones,
-            white_caps: caps,
-            black_caps: caps,
-            board,
-            to_move,
-            ply,
-        };
-        game.play_ptn_moves(&moves)?;
-        Ok(game)
+impl<const N: usize> ToPTN for GameRecord<N> {
+    fn to_ptn(&self) -> String {
+        PtnGame::from(self).to_ptn()
     }
 }
 
 impl<const N: usize> Game<N> {
-    pub fn play_ptn_moves(&mut self, moves: &[&str]) -> StrResult<()>
-    where
-        [[Option<Tile>; N]; N]: Default,
-    {
+    pub fn play_ptn_moves(&mut self, moves: &[&str]) -> TakResult<()> {
         for ply in moves {
             let turn = Turn::from_ptn(ply)?;
             self.play(turn)?;
@@ -278,16 +295,11 @@ impl<const N: usize> Game<N> {
 }
 
 impl<const N: usize> FromPTN for Vec<Turn<N>> {
-    fn from_ptn(str: &str) -> StrResult<Self> {
-        // remove comments
-        let s = OPTIONS_RE.replace_all(str, "");
-        let s = COMMENTS_RE.replace_all(&s, "");
-
-        // get individual plies (split at move numbers, space, and game result)
-        PLY_SPLIT_RE
-            .split(&s)
-            .filter(|ss| !ss.is_empty())
-            .map(Turn::from_ptn)
-            .collect()
+    fn from_ptn(s: &str) -> TakResult<Self> {
+        Ok(PtnGame::from_ptn(s)?
+            .plies
+            .into_iter()
+            .map(|ply| ply.turn)
+            .collect())
     }
 }
@@ -0,0 +1,400 @@
+use crate::{
+    colour::Colour,
+    error::{Error, TakResult},
+    game::Game,
+    ptn::{FromPTN, ToPTN, ANNOTATIONS},
+    record::GameRecord,
+    tps::{game_from_tps, opening_ptn, parse_opening, parse_turn_limit, turn_limit_ptn, ToTPS},
+    turn::Turn,
+};
+
+/// Result tokens which can end the move list.
+const RESULTS: [&str; 8] = ["R-0", "0-R", "F-0", "0-F", "1-0", "0-1", "1/2-1/2", "0-0"];
+
+/// A PTN document, keeping everything needed to write it back out.
+///
+/// Comments cannot contain `}`, since PTN has no way to escape it.
+#[derive(Clone, Debug, Default)]
+pub struct PtnGame<const N: usize> {
+    /// Tag pairs in the order they were written, like `("Player1", "alion02")`.
+    pub tags: Vec<(String, String)>,
+    /// Comments between the tags, with the index of the tag they come
+    /// before.
+    pub tag_comments: Vec<(usize, String)>,
+    /// Comments after the tags and before the first ply.
+    pub comments: Vec<String>,
+    pub plies: Vec<PtnPly<N>>,
+    /// Result token after the last ply, like `R-0` or `1/2-1/2`.
+    pub result: Option<String>,
+    /// Comments after the result.
+    pub result_comments: Vec<String>,
+    /// The document this was parsed from, to report problems with the tags
+    /// at their place in it.
+    source: Option<Source>,
+}
+
+#[derive(Clone, Debug)]
+struct Source {
+    text: String,
+    /// Byte offset of each tag value in the text.
+    values: Vec<usize>,
+}
+
+impl<const N: usize> PartialEq for PtnGame<N> {
+    fn eq(&self, other: &Self) -> bool {
+        self.tags == other.tags
+            && self.tag_comments == other.tag_comments
+            && self.comments == other.comments
+            && self.plies == other.plies
+            && self.result == other.result
+            && self.result_comments == other.result_comments
+    }
+}
+
+impl<const N: usize> Eq for PtnGame<N> {}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PtnPly<const N: usize> {
+    pub turn: Turn<N>,
+    /// Marks written right after the turn, like `'` or `?!`.
+    pub annotation: String,
+    /// Comments after this ply.
+    pub comments: Vec<String>,
+}
+
+impl<const N: usize> PtnPly<N> {
+    pub fn new(turn: Turn<N>) -> Self {
+        PtnPly {
+            turn,
+            annotation: String::new(),
+            comments: Vec::new(),
+        }
+    }
+}
+
+impl<const N: usize> PtnGame<N> {
+    /// Get the value of the first tag with this key.
+    pub fn tag(&self, key: &str) -> Option<&str> {
+        self.tags
+            .iter()
+            .find(|(k, _)| k == key)
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// Replace the value of the tag, or add it at the end if it is missing.
+    pub fn set_tag(&mut self, key: &str, value: impl Into<String>) {
+        let value = value.into();
+        match self.tags.iter_mut().find(|(k, _)| k == key) {
+            Some((_, v)) => *v = value,
+            None => self.tags.push((key.to_string(), value)),
+        }
+    }
+
+    /// Set up the starting position from the Size, Komi, Flats, Caps,
+    /// Opening, TurnLimit and TPS tags. Missing rules are the standard ones.
+    ///
+    /// The opening is `swap` or `no-swap`, and the turn limit is counted
+    /// in plies like [`Rules::turn_limit`], or `none`.
+    ///
+    /// [`Rules::turn_limit`]: crate::Rules::turn_limit
+    pub fn start(&self) -> TakResult<Game<N>> {
+        let mut rules = Game::<N>::standard_rules();
+        let mut tps = None;
+        for (i, (key, value)) in self.tags.iter().enumerate() {
+            let err = |message| self.tag_error(i, 0, message);
+            match key.as_str() {
+                "Komi" => rules.komi = value.parse().map_err(|_| err("cannot parse komi"))?,
+                "Flats" => rules.stones = value.parse::<u8>().map_err(|_| err("cannot parse flats"))?,
+                "Caps" => rules.caps = value.parse::<u8>().map_err(|_| err("cannot parse caps"))?,
+                "Opening" => rules.swap = parse_opening(value).ok_or_else(|| err("unknown opening"))?,
+                "TurnLimit" => {
+                    rules.turn_limit =
+                        parse_turn_limit(value).ok_or_else(|| err("cannot parse turn limit"))?
+                }
+                "Size" => {
+                    if value.parse::<usize>().map_err(|_| err("cannot parse size"))? != N {
+                        return Err(err("game size mismatch"));
+                    }
+                }
+                "TPS" => tps = Some((i, value)),
+                _ => {}
+            }
+        }
+
+        // the position is set up after all tags are known
+        match tps {
+            Some((i, tps)) => game_from_tps(tps, rules).map_err(|e| match e {
+                Error::ParseError {
+                    position, message, ..
+                } => self.tag_error(i, position, &message),
+                e => e,
+            }),
+            None => Game::with_rules(rules),
+        }
+    }
+
+    /// Make an error at this offset in the value of a tag. The offset is
+    /// in the document the tag was read from, if the value is still
+    /// written the same there.
+    fn tag_error(&self, tag: usize, at: usize, message: &str) -> Error {
+        let value = &self.tags[tag].1;
+        if let Some(source) = &self.source {
+            let offset = source.values.get(tag).copied();
+            if let Some(offset) = offset.filter(|&offset| source.text[offset..].starts_with(value.as_str())) {
+                return Error::parse(&source.text, offset + at, message);
+            }
+        }
+        Error::parse(value, at, message)
+    }
+
+    /// Play out the plies from the starting position.
+    pub fn record(&self) -> TakResult<GameRecord<N>> {
+        let mut record = GameRecord::new(self.start()?);
+        for ply in &self.plies {
+            record.play(ply.turn.clone())?;
+        }
+        Ok(record)
+    }
+
+    /// Move number and colour of the first ply, taken from the TPS tag.
+    fn first_move(&self) -> (u64, Colour) {
+        let mut fields = self.tag("TPS").unwrap_or_default().split_whitespace().skip(1);
+        let colour = match fields.next() {
+            Some("2") => Colour::Black,
+            _ => Colour::White,
+        };
+        let move_num = fields.next().and_then(|n| n.parse().ok()).unwrap_or(1);
+        (move_num, colour)
+    }
+}
+
+impl<const N: usize> From<&GameRecord<N>> for PtnGame<N> {
+    fn from(record: &GameRecord<N>) -> Self {
+        let start = record.start();
+        let mut tags = vec![
+            ("Size".to_string(), N.to_string()),
+            ("Komi".to_string(), start.rules.komi.to_string()),
+            ("Flats".to_string(), start.rules.stones.to_string()),
+            ("Caps".to_string(), start.rules.caps.to_string()),
+        ];
+        // other tools do not know these tags, so they are only written for
+        // rules which are not the standard ones
+        let standard = Game::<N>::standard_rules();
+        if start.rules.swap != standard.swap {
+            tags.push(("Opening".to_string(), opening_ptn(start.rules.swap).to_string()));
+        }
+        if start.rules.turn_limit != standard.turn_limit {
+            tags.push(("TurnLimit".to_string(), turn_limit_ptn(start.rules.turn_limit)));
+        }
+        if start.ply > 0 || !start.board.empty() {
+            tags.push((
+                "TPS".to_string(),
+                format!(
+                    "{} {} {}",
+                    start.board.to_tps(),
+                    start.to_move.to_ptn(),
+                    start.ply / 2 + 1
+                ),
+            ));
+        }
+        let result = record.game().outcome().result_ptn().map(str::to_string);
+        if let Some(result) = &result {
+            tags.push(("Result".to_string(), result.clone()));
+        }
+
+        PtnGame {
+            tags,
+            tag_comments: Vec::new(),
+            comments: Vec::new(),
+            plies: record.turns().cloned().map(PtnPly::new).collect(),
+            result,
+            result_comments: Vec::new(),
+            source: None,
+        }
+    }
+}
+
+impl<const N: usize> FromPTN for PtnGame<N> {
+    fn from_ptn(s: &str) -> TakResult<Self> {
+        let mut game = PtnGame::default();
+        let mut values = Vec::new();
+        let mut rest = s;
+        loop {
+            rest = rest.trim_start();
+            let offset = s.len() - rest.len();
+            let err = |at: usize, message: &str| Error::parse(s, offset + at, message);
+
+            if rest.is_empty() {
+                break;
+            } else if let Some(tag) = rest.strip_prefix('[') {
+                if !game.plies.is_empty() || game.result.is_some() {
+                    return Err(err(0, "tag pair after the moves"));
+                }
+                let (key, value, value_at, len) =
+                    parse_tag(tag).map_err(|(at, message)| err(1 + at, message))?;
+                if key == "Size" && value.parse::<usize>() != Ok(N) {
+                    return Err(err(0, "game size mismatch"));
+                }
+                // the comments so far turn out to be between the tags
+                let before = game.tags.len();
+                game.tag_comments
+                    .extend(game.comments.drain(..).map(|comment| (before, comment)));
+                game.tags.push((key, value));
+                values.push(offset + 1 + value_at);
+                rest = &tag[len..];
+            } else if let Some(comment) = rest.strip_prefix('{') {
+                let end = comment.find('}').ok_or_else(|| err(0, "unclosed comment"))?;
+                let comments = match game.plies.last_mut() {
+                    _ if game.result.is_some() => &mut game.result_comments,
+                    Some(ply) => &mut ply.comments,
+                    None => &mut game.comments,
+                };
+                comments.push(comment[..end].to_string());
+                rest = &comment[end + 1..];
+            } else {
+                let len = rest
+                    .find(|c: char| c.is_whitespace() || c == '{' || c == '[')
+                    .unwrap_or(rest.len());
+                let (token, after) = rest.split_at(len);
+                rest = after;
+
+                if game.result.is_some() {
+                    return Err(err(0, "moves after the result"));
+                }
+
+                // a move number can be written right before the ply, like `1.a1`
+                let digits = token.find(|c: char| !c.is_ascii_digit()).unwrap_or(token.len());
+                let (token, at) = match token[digits..].strip_prefix('.') {
+                    Some(ply) if digits > 0 => (ply, digits + 1),
+                    _ => (token, 0),
+                };
+
+                if token.is_empty() || token == "--" {
+                    continue;
+                } else if RESULTS.contains(&token) {
+                    game.result = Some(token.to_string());
+                } else {
+                    let turn = token.trim_end_matches(ANNOTATIONS);
+                    game.plies.push(PtnPly {
+                        turn: Turn::from_ptn(turn).map_err(|e| match e {
+                            Error::ParseError {
+                                position, message, ..
+                            } => err(at + position, &message),
+                            e => e,
+                        })?,
+                        annotation: token[turn.len()..].to_string(),
+                        comments: Vec::new(),
+                    });
+                }
+            }
+        }
+        game.source = Some(Source {
+            text: s.to_string(),
+            values,
+        });
+        Ok(game)
+    }
+}
+
+/// Whether a tag value can be quoted with this character.
+pub(crate) fn is_quote(c: char) -> bool {
+    c == '"' || c == '\''
+}
+
+/// Parse the inside of a tag pair like `Key "value"]`.
+/// Returns the key, the unescaped value, the offset of the value and the
+/// length read, or the offset and description of the problem.
+fn parse_tag(s: &str) -> Result<(String, String, usize, usize), (usize, &'static str)> {
+    let key_len = s.find(char::is_whitespace).ok_or((0, "unclosed tag pair"))?;
+    let key = &s[..key_len];
+    if key.is_empty() || key.contains(|c| c == '[' || c == ']' || is_quote(c)) {
+        return Err((0, "invalid tag name"));
+    }
+
+    let quoted = s[key_len..].trim_start();
+    let mut at = s.len() - quoted.len();
+    let quote = quoted
+        .chars()
+        .next()
+        .filter(|&c| is_quote(c))
+        .ok_or((at, "expected a quoted tag value"))?;
+
+    let value_at = at + 1;
+    let mut value = String::new();
+    let mut chars = quoted[1..].char_indices();
+    let end = loop {
+        match chars.next() {
+            Some((_, '\\')) => value.push(chars.next().ok_or((at, "unclosed tag value"))?.1),
+            Some((i, c)) if c == quote => break i,
+            Some((_, c)) => value.push(c),
+            None => return Err((at, "unclosed tag value")),
+        }
+    };
+    at += 1 + end + 1;
+
+    let close = s[at..].trim_start();
+    at = s.len() - close.len();
+    if !close.starts_with(']') {
+        return Err((at, "expected `]` after the tag value"));
+    }
+    Ok((key.to_string(), value, value_at, at + 1))
+}
+
+impl<const N: usize> ToPTN for PtnGame<N> {
+    /// Writes one move per line, with comments after the plies they
+    /// belong to.
+    fn to_ptn(&self) -> String {
+        let mut out = String::new();
+        for (i, (key, value)) in self.tags.iter().enumerate() {
+            for (_, comment) in self.tag_comments.iter().filter(|(tag, _)| *tag == i) {
+                out.push_str(&format!("{{{comment}}}\n"));
+            }
+            let value = value.replace('\\', "\\\\").replace('"', "\\\"");
+            out.push_str(&format!("[{key} \"{value}\"]\n"));
+        }
+        // comments before tags which are gone are kept after the others
+        for (_, comment) in self
+            .tag_comments
+            .iter()
+            .filter(|(tag, _)| *tag >= self.tags.len())
+        {
+            out.push_str(&format!("{{{comment}}}\n"));
+        }
+        for comment in &self.comments {
+            out.push_str(&format!("{{{comment}}}\n"));
+        }
+
+        let (mut move_num, mut colour) = self.first_move();
+        for (i, ply) in self.plies.iter().enumerate() {
+            if colour == Colour::White {
+                out.push_str(&format!("{move_num}. "));
+            } else if i == 0 {
+                out.push_str(&format!("{move_num}. -- "));
+            } else {
+                out.push(' ');
+            }
+            out.push_str(&ply.turn.to_ptn());
+            out.push_str(&ply.annotation);
+            for comment in &ply.comments {
+                out.push_str(&format!(" {{{comment}}}"));
+            }
+            if colour == Colour::Black {
+                out.push('\n');
+                move_num += 1;
+            }
+            colour = colour.next();
+        }
+        if !self.plies.is_empty() && colour == Colour::Black {
+            out.push('\n');
+        }
+
+        if let Some(result) = &self.result {
+            out.push_str(result);
+            for comment in &self.result_comments {
+                out.push_str(&format!(" {{{comment}}}"));
+            }
+            out.push('\n');
+        }
+        out
+    }
+}
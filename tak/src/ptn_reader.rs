@@ -0,0 +1,168 @@
+use std::io::{BufRead, BufReader, Lines, Read};
+
+use crate::{
+    error::TakResult,
+    ptn::FromPTN,
+    ptn_game::{is_quote, PtnGame},
+};
+
+/// A game read by [`PtnReader`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DatabaseGame<const N: usize> {
+    /// Line where the game starts, counting from 1.
+    pub line: usize,
+    pub game: TakResult<PtnGame<N>>,
+}
+
+/// Reads games one at a time from PTN written back to back, like the game
+/// dumps from PlayTak. A new game starts at the first tag pair after the
+/// moves of the previous one. When the previous one has no moves, it ends
+/// at a blank line followed by a tag pair it already has, so blank lines
+/// can still be written between its tags.
+///
+/// Every game is parsed on its own, so a corrupt game only makes its own
+/// entry an error. Reading stops after an I/O error.
+pub struct PtnReader<R: Read, const N: usize> {
+    lines: Lines<BufReader<R>>,
+    /// Number of lines read so far.
+    line: usize,
+    /// First line of the next game, read while looking for the end of the
+    /// previous one.
+    next: Option<(usize, String)>,
+    done: bool,
+}
+
+impl<R: Read, const N: usize> PtnReader<R, N> {
+    pub fn new(reader: R) -> Self {
+        PtnReader {
+            lines: BufReader::new(reader).lines(),
+            line: 0,
+            next: None,
+            done: false,
+        }
+    }
+
+    fn read_line(&mut self) -> Option<std::io::Result<String>> {
+        let line = self.lines.next()?;
+        self.line += 1;
+        Some(line)
+    }
+}
+
+impl<R: Read, const N: usize> Iterator for PtnReader<R, N> {
+    type Item = DatabaseGame<N>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+
+        // skip blank lines before the game
+        let (start, first) = match self.next.take() {
+            Some(next) => next,
+            None => loop {
+                match self.read_line()? {
+                    Ok(line) if line.trim().is_empty() => {}
+                    Ok(line) => break (self.line, line),
+                    Err(err) => {
+                        self.done = true;
+                        return Some(DatabaseGame {
+                            line: self.line,
+                            game: Err(err.into()),
+                        });
+                    }
+                }
+            },
+        };
+
+        let mut scan = Scan::default();
+        scan.line(&first);
+        let mut text = first;
+        text.push('\n');
+        while let Some(line) = self.read_line() {
+            let line = match line {
+                Ok(line) => line,
+                Err(err) => {
+                    self.done = true;
+                    return Some(DatabaseGame {
+                        line: start,
+                        game: Err(err.into()),
+                    });
+                }
+            };
+            let new_game = match tag_key(&line) {
+                Some(key) if !scan.comment => {
+                    scan.moves || (scan.blank && scan.keys.iter().any(|k| k == key))
+                }
+                _ => false,
+            };
+            if new_game {
+                self.next = Some((self.line, line));
+                break;
+            }
+            scan.line(&line);
+            text.push_str(&line);
+            text.push('\n');
+        }
+
+        Some(DatabaseGame {
+            line: start,
+            game: PtnGame::from_ptn(&text),
+        })
+    }
+}
+
+/// What has been seen so far in a game.
+#[derive(Default)]
+struct Scan {
+    comment: bool,
+    tag: bool,
+    /// The quote around the value of a tag pair being read, inside of which
+    /// `]` does not end it.
+    quote: Option<char>,
+    escaped: bool,
+    moves: bool,
+    /// A blank line outside of a comment since the last tag pair.
+    blank: bool,
+    /// Keys of the tag pairs.
+    keys: Vec<String>,
+}
+
+impl Scan {
+    fn line(&mut self, line: &str) {
+        if !self.comment && line.trim().is_empty() {
+            self.blank = true;
+        }
+        for (i, c) in line.char_indices() {
+            if self.comment {
+                self.comment = c != '}';
+            } else if self.escaped {
+                self.escaped = false;
+            } else if let Some(quote) = self.quote {
+                self.escaped = c == '\\';
+                self.quote = Some(quote).filter(|&quote| c != quote);
+            } else if self.tag {
+                self.quote = Some(c).filter(|&c| is_quote(c));
+                self.tag = c != ']';
+            } else if c == '{' {
+                self.comment = true;
+            } else if c == '[' {
+                self.tag = true;
+                self.blank = false;
+                self.keys.extend(tag_key(&line[i..]).map(str::to_string));
+            } else if !c.is_whitespace() {
+                self.moves = true;
+            }
+        }
+        // tag pairs never span lines, so a broken one ends here
+        self.tag = false;
+        self.quote = None;
+        self.escaped = false;
+    }
+}
+
+/// Key of the tag pair at the start of the line, if there is one.
+fn tag_key(line: &str) -> Option<&str> {
+    let tag = line.trim_start().strip_prefix('[')?;
+    tag.split(char::is_whitespace).next()
+}
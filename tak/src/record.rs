@@ -0,0 +1,60 @@
+use crate::{
+    error::TakResult,
+    game::{Game, Undo},
+    turn::Turn,
+};
+
+/// A game together with the position it started from and the turns
+/// played since, so that it can be written as PTN.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct GameRecord<const N: usize> {
+    start: Game<N>,
+    game: Game<N>,
+    history: Vec<Undo<N>>,
+}
+
+impl<const N: usize> GameRecord<N> {
+    /// Start recording from this position.
+    pub fn new(start: Game<N>) -> Self {
+        GameRecord {
+            game: start.clone(),
+            start,
+            history: Vec::new(),
+        }
+    }
+
+    /// The position before any of the recorded turns.
+    pub fn start(&self) -> &Game<N> {
+        &self.start
+    }
+
+    /// The current position.
+    pub fn game(&self) -> &Game<N> {
+        &self.game
+    }
+
+    pub fn into_game(self) -> Game<N> {
+        self.game
+    }
+
+    /// Turns played since the start, in order.
+    pub fn turns(&self) -> impl DoubleEndedIterator<Item = &Turn<N>> + ExactSizeIterator {
+        self.history.iter().map(Undo::turn)
+    }
+
+    /// Play the turn and add it to the record.
+    /// An illegal turn leaves both the game and the record unchanged.
+    pub fn play(&mut self, turn: Turn<N>) -> TakResult<()> {
+        let undo = self.game.play_undoable(turn)?;
+        self.history.push(undo);
+        Ok(())
+    }
+
+    /// Take back the last turn and remove it from the record.
+    pub fn undo(&mut self) -> Option<Turn<N>> {
+        let undo = self.history.pop()?;
+        let turn = undo.turn().clone();
+        self.game.undo(undo);
+        Some(turn)
+    }
+}
@@ -0,0 +1,83 @@
+//! Serde support, writing positions as TPS and turns as PTN so that the
+//! JSON stays readable and does not change with the internal layout.
+
+use std::str::FromStr;
+
+use serde::{de::Error as _, Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::{
+    board::Board,
+    game::{Game, Rules},
+    komi::Komi,
+    pos::Pos,
+    ptn::{FromPTN, ToPTN},
+    tile::{Piece, Tile},
+    tps::{game_from_tps, FromTPS, ToTPS},
+    turn::Turn,
+};
+
+/// Implement serde for a type through one of its string forms.
+macro_rules! impl_string_serde {
+    ([$($generics:tt)*] $ty:ty, $to:ident, $from:ident) => {
+        impl<$($generics)*> Serialize for $ty {
+            fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                serializer.serialize_str(&self.$to())
+            }
+        }
+
+        impl<'de, $($generics)*> Deserialize<'de> for $ty {
+            fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                let s = String::deserialize(deserializer)?;
+                <$ty>::$from(&s).map_err(D::Error::custom)
+            }
+        }
+    };
+}
+
+impl_string_serde!([const N: usize] Pos<N>, to_ptn, from_ptn);
+impl_string_serde!([const N: usize] Turn<N>, to_ptn, from_ptn);
+impl_string_serde!([] Tile, to_tps, from_tps);
+impl_string_serde!([] Komi, to_string, from_str);
+impl_string_serde!([const N: usize] Board<N>, to_tps, from_tps);
+
+/// A game is written as extended TPS next to its rules, which the TPS
+/// only covers the komi of.
+#[derive(Serialize, Deserialize)]
+struct GameForm {
+    tps: String,
+    rules: Rules,
+}
+
+impl<const N: usize> Serialize for Game<N> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        GameForm {
+            tps: self.to_tps(),
+            rules: self.rules,
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de, const N: usize> Deserialize<'de> for Game<N> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let form = GameForm::deserialize(deserializer)?;
+        game_from_tps(&form.tps, form.rules).map_err(D::Error::custom)
+    }
+}
+
+/// A piece is written like a tile with nothing under it.
+impl Serialize for Piece {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        Tile::new(*self).serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Piece {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let tile = Tile::deserialize(deserializer)?;
+        if !tile.stack.is_empty() {
+            return Err(D::Error::custom("expected a single piece"));
+        }
+        Ok(tile.top)
+    }
+}
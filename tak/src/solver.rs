@@ -0,0 +1,243 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::{
+    colour::Colour,
+    game::{Game, GameResult},
+    turn::Turn,
+    zobrist::TableKey,
+};
+
+/// Large enough to never be reached by adding up real proof numbers,
+/// while sums of two never overflow.
+const INFINITY: u64 = u64::MAX / 4;
+
+/// Game theoretic value of a position for the player to move.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Solution {
+    Win,
+    Loss,
+    Draw,
+}
+
+/// Proof and disproof numbers for one colour winning.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct Proof {
+    pn: u64,
+    dn: u64,
+}
+
+impl Proof {
+    const DISPROVEN: Proof = Proof { pn: INFINITY, dn: 0 };
+    const PROVEN: Proof = Proof { pn: 0, dn: INFINITY };
+    const UNKNOWN: Proof = Proof { pn: 1, dn: 1 };
+}
+
+/// Depth-first proof-number search, which proves whether a colour can
+/// force a win. Positions are stored in their canonical form, so the table
+/// fills up across searches and can be queried afterwards.
+///
+/// Repeating a position on the current line counts as not winning, since
+/// it can only end in a draw by the turn limit. Like any transposition
+/// table without the path this is not exact when positions repeat.
+#[derive(Clone, Debug, Default)]
+pub struct Solver<const N: usize> {
+    /// Results for White winning, then for Black winning.
+    tables: [HashMap<TableKey<N>, Proof>; 2],
+    /// Positions on the line being searched.
+    path: HashSet<TableKey<N>>,
+}
+
+impl<const N: usize> Solver<N> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Number of positions in the table.
+    pub fn len(&self) -> usize {
+        self.tables.iter().map(HashMap::len).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Prove or disprove that this colour can force a win.
+    pub fn proves(&mut self, game: &Game<N>, colour: Colour) -> bool {
+        match game.winner() {
+            GameResult::Winner { colour: winner, .. } => return winner == colour,
+            GameResult::Draw { .. } => return false,
+            GameResult::Ongoing => {}
+        }
+        let proof = self.search(&mut game.clone(), colour, Proof {
+            pn: INFINITY,
+            dn: INFINITY,
+        });
+        proof.pn == 0
+    }
+
+    /// Find the value of the position for the player to move.
+    pub fn solve(&mut self, game: &Game<N>) -> Solution {
+        if self.proves(game, game.to_move) {
+            Solution::Win
+        } else if self.proves(game, game.to_move.next()) {
+            Solution::Loss
+        } else {
+            Solution::Draw
+        }
+    }
+
+    /// Look up the value of a position which has been solved.
+    pub fn get(&self, game: &Game<N>) -> Option<Solution> {
+        match game.winner() {
+            GameResult::Winner { colour, .. } if colour == game.to_move => return Some(Solution::Win),
+            GameResult::Winner { .. } => return Some(Solution::Loss),
+            GameResult::Draw { .. } => return Some(Solution::Draw),
+            GameResult::Ongoing => {}
+        }
+        let position = table_key(game);
+        let wins = |colour| {
+            self.table(colour)
+                .get(&position)
+                .copied()
+                .unwrap_or(Proof::UNKNOWN)
+        };
+        match (wins(game.to_move), wins(game.to_move.next())) {
+            (us, _) if us.pn == 0 => Some(Solution::Win),
+            (_, them) if them.pn == 0 => Some(Solution::Loss),
+            (us, them) if us.dn == 0 && them.dn == 0 => Some(Solution::Draw),
+            _ => None,
+        }
+    }
+
+    /// Follow the solved positions from here until the game ends.
+    /// The winner plays a turn which keeps the win, and otherwise the
+    /// first turn with the right value is taken. Stops early when the
+    /// table does not know the next position.
+    pub fn principal_line(&self, game: &Game<N>) -> Vec<Turn<N>> {
+        let mut game = game.clone();
+        let mut line = Vec::new();
+        let mut seen = HashSet::new();
+        while let Some(solution) = self.get(&game) {
+            if game.winner() != GameResult::Ongoing || !seen.insert(table_key(&game)) {
+                break;
+            }
+            let reply = match solution {
+                Solution::Win => Solution::Loss,
+                Solution::Loss => Solution::Win,
+                Solution::Draw => Solution::Draw,
+            };
+            let next = game.possible_turns().into_iter().find_map(|turn| {
+                let mut next = game.clone();
+                next.play(turn.clone()).expect("possible turns should be legal");
+                (self.get(&next) == Some(reply)).then_some((turn, next))
+            });
+            match next {
+                Some((turn, next)) => {
+                    line.push(turn);
+                    game = next;
+                }
+                None => break,
+            }
+        }
+        line
+    }
+
+    fn table(&self, colour: Colour) -> &HashMap<TableKey<N>, Proof> {
+        &self.tables[colour as usize]
+    }
+
+    fn table_mut(&mut self, colour: Colour) -> &mut HashMap<TableKey<N>, Proof> {
+        &mut self.tables[colour as usize]
+    }
+
+    /// Search until the proof or disproof number reaches its threshold.
+    fn search(&mut self, game: &mut Game<N>, attacker: Colour, threshold: Proof) -> Proof {
+        let position = table_key(game);
+        let attacking = game.to_move == attacker;
+        self.path.insert(position.clone());
+
+        // children which are decided right away keep their value here
+        let mut children = Vec::new();
+        for turn in game.possible_turns() {
+            let undo = game.play_undoable(turn).expect("possible turns should be legal");
+            let child = table_key(game);
+            let fixed = match game.winner() {
+                GameResult::Winner { colour, .. } if colour == attacker => Some(Proof::PROVEN),
+                GameResult::Ongoing if !self.path.contains(&child) => None,
+                _ => Some(Proof::DISPROVEN),
+            };
+            children.push((undo.turn().clone(), child, fixed));
+            game.undo(undo);
+        }
+
+        let proof = loop {
+            let proofs: Vec<_> = children
+                .iter()
+                .map(|(_, child, fixed)| {
+                    fixed
+                        .unwrap_or_else(|| self.table(attacker).get(child).copied().unwrap_or(Proof::UNKNOWN))
+                })
+                .collect();
+            let sum = |f: fn(&Proof) -> u64| proofs.iter().map(f).fold(0, |a, b| (a + b).min(INFINITY));
+            let min = |f: fn(&Proof) -> u64| proofs.iter().map(f).min().unwrap_or(INFINITY);
+            let proof = if attacking {
+                Proof {
+                    pn: min(|p| p.pn),
+                    dn: sum(|p| p.dn),
+                }
+            } else {
+                Proof {
+                    pn: sum(|p| p.pn),
+                    dn: min(|p| p.dn),
+                }
+            };
+            if proof.pn >= threshold.pn || proof.dn >= threshold.dn {
+                break proof;
+            }
+
+            // the most promising child and the number it has to beat
+            let number = |p: &Proof| if attacking { p.pn } else { p.dn };
+            let mut best = 0;
+            let mut second = INFINITY;
+            for (i, p) in proofs.iter().enumerate().skip(1) {
+                if number(p) < number(&proofs[best]) {
+                    second = number(&proofs[best]);
+                    best = i;
+                } else if number(p) < second {
+                    second = number(p);
+                }
+            }
+            let child_threshold = if attacking {
+                Proof {
+                    pn: threshold.pn.min(second + 1),
+                    dn: threshold.dn - proof.dn + proofs[best].dn,
+                }
+            } else {
+                Proof {
+                    pn: threshold.pn - proof.pn + proofs[best].pn,
+                    dn: threshold.dn.min(second + 1),
+                }
+            };
+
+            let turn = children[best].0.clone();
+            let undo = game.play_undoable(turn).expect("possible turns should be legal");
+            let child = self.search(game, attacker, child_threshold);
+            game.undo(undo);
+            self.table_mut(attacker).insert(children[best].1.clone(), child);
+        };
+        self.path.remove(&position);
+
+        self.table_mut(attacker).insert(position, proof);
+        proof
+    }
+}
+
+/// Key of the position in the tables, the same for all its symmetries.
+/// The ply only matters for the opening, unless there is a turn limit.
+fn table_key<const N: usize>(game: &Game<N>) -> TableKey<N> {
+    let mut position = game.canonical().0;
+    if position.rules.turn_limit.is_none() {
+        position.ply = position.ply.min(2);
+    }
+    TableKey(position)
+}
@@ -0,0 +1,213 @@
+use crate::{bitboard::Bitboard, colour::Colour, game::Game, pos::Pos, tile::Shape, turn::Turn};
+
+/// Side of a square in pixels.
+const SQUARE: usize = 64;
+/// Space above and right of the board.
+const PADDING: usize = 8;
+/// Space left of and below the board for the coordinates.
+const MARGIN: usize = 24;
+/// Space below the coordinates for the reserves.
+const FOOTER: usize = 48;
+
+const LIGHT: &str = "#f4efe1";
+const DARK: &str = "#2b2b2b";
+const HIGHLIGHT: &str = "#1f6fd1";
+
+/// Extra things to draw with [`Game::to_svg`].
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct SvgOptions<const N: usize> {
+    /// The turn which led to the position, drawn as an arrow for a move and
+    /// as an outline for a placement.
+    pub last_turn: Option<Turn<N>>,
+    /// A value from 0 to 1 for each square, indexed `[y][x]` like the
+    /// board, shaded over the squares.
+    pub heatmap: Option<[[f32; N]; N]>,
+    /// Squares to outline, like the road of [`EndReason::Road`].
+    ///
+    /// [`EndReason::Road`]: crate::EndReason::Road
+    pub road: Option<Bitboard<N>>,
+}
+
+impl<const N: usize> Game<N> {
+    /// Draw the position as an SVG image, with a1 in the bottom left. The
+    /// flats under the top of a stack are drawn bottom to top on the left
+    /// of their square, and the reserves are listed under the board.
+    ///
+    /// The parts of the image have classes such as `heat`, `road` and
+    /// `last-turn`, so they can be restyled.
+    pub fn to_svg(&self, options: &SvgOptions<N>) -> String {
+        let board = N * SQUARE;
+        let width = MARGIN + board + PADDING;
+        let height = PADDING + board + MARGIN + FOOTER;
+        let corner = |pos: Pos<N>| (MARGIN + pos.x * SQUARE, PADDING + (N - 1 - pos.y) * SQUARE);
+        let centre = |pos: Pos<N>| {
+            let (left, top) = corner(pos);
+            (left + SQUARE / 2, top + SQUARE / 2)
+        };
+
+        let mut svg = format!(
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{width}\" height=\"{height}\" viewBox=\"0 0 \
+             {width} {height}\" font-family=\"sans-serif\">\n"
+        );
+        svg.push_str(&format!(
+            "<defs><marker id=\"arrow\" viewBox=\"0 0 10 10\" refX=\"8\" refY=\"5\" markerWidth=\"4\" \
+             markerHeight=\"4\" orient=\"auto\"><path d=\"M 0 0 L 10 5 L 0 10 z\" \
+             fill=\"{HIGHLIGHT}\"/></marker></defs>\n"
+        ));
+        svg.push_str(&format!(
+            "<rect width=\"{width}\" height=\"{height}\" fill=\"#f7f3ea\"/>\n"
+        ));
+
+        // squares with their overlays
+        for pos in Bitboard::<N>::full().positions() {
+            let (left, top) = corner(pos);
+            svg.push_str(&format!(
+                "<rect class=\"square\" x=\"{left}\" y=\"{top}\" width=\"{SQUARE}\" height=\"{SQUARE}\" \
+                 fill=\"#c9b38b\" stroke=\"#8c7853\"/>\n"
+            ));
+            let heat = options.heatmap.map_or(0., |heatmap| heatmap[pos.y][pos.x]);
+            if heat > 0. {
+                svg.push_str(&format!(
+                    "<rect class=\"heat\" x=\"{left}\" y=\"{top}\" width=\"{SQUARE}\" height=\"{SQUARE}\" \
+                     fill=\"#e4572e\" fill-opacity=\"{:.3}\"/>\n",
+                    0.7 * heat.min(1.)
+                ));
+            }
+            if matches!(options.road, Some(road) if road.contains(pos)) {
+                svg.push_str(&format!(
+                    "<rect class=\"road\" x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" fill=\"none\" \
+                     stroke=\"#2f9e44\" stroke-width=\"4\"/>\n",
+                    left + 3,
+                    top + 3,
+                    SQUARE - 6,
+                    SQUARE - 6
+                ));
+            }
+        }
+
+        // coordinates
+        for i in 0..N {
+            let (x, y) = centre(Pos { x: i, y: i });
+            svg.push_str(&format!(
+                "<text x=\"{x}\" y=\"{}\" font-size=\"14\" text-anchor=\"middle\">{}</text>\n",
+                PADDING + board + 17,
+                (b'a' + i as u8) as char
+            ));
+            svg.push_str(&format!(
+                "<text x=\"{}\" y=\"{}\" font-size=\"14\" text-anchor=\"middle\">{}</text>\n",
+                MARGIN / 2,
+                y + 5,
+                i + 1
+            ));
+        }
+
+        // stacks
+        for pos in self.board.occupied().positions() {
+            let tile = self.board[pos].expect("occupied squares should have a tile");
+            let (left, top) = corner(pos);
+            let height = ((SQUARE - 12) as f32 / tile.stack.len() as f32).min(6.);
+            for (i, colour) in tile.stack.iter().enumerate() {
+                svg.push_str(&format!(
+                    "<rect class=\"buried\" x=\"{}\" y=\"{:.1}\" width=\"8\" height=\"{height:.1}\" \
+                     fill=\"{}\" stroke=\"{DARK}\" stroke-width=\"0.5\"/>\n",
+                    left + 5,
+                    (top + SQUARE - 6) as f32 - (i + 1) as f32 * height,
+                    fill(colour)
+                ));
+            }
+
+            let (x, y) = (left + SQUARE / 2 + 4, top + SQUARE / 2);
+            let style = format!(
+                "fill=\"{}\" stroke=\"{DARK}\" stroke-width=\"1.5\"",
+                fill(tile.top.colour)
+            );
+            svg.push_str(&match tile.top.shape {
+                Shape::Flat => format!(
+                    "<rect class=\"flat\" x=\"{}\" y=\"{}\" width=\"32\" height=\"32\" rx=\"4\" {style}/>\n",
+                    x - 16,
+                    y - 16
+                ),
+                Shape::Wall => format!(
+                    "<rect class=\"wall\" x=\"{}\" y=\"{}\" width=\"12\" height=\"36\" rx=\"2\" \
+                     transform=\"rotate(45 {x} {y})\" {style}/>\n",
+                    x - 6,
+                    y - 18
+                ),
+                Shape::Capstone => {
+                    format!("<circle class=\"capstone\" cx=\"{x}\" cy=\"{y}\" r=\"15\" {style}/>\n")
+                }
+            });
+            if tile.size() > 1 {
+                svg.push_str(&format!(
+                    "<text x=\"{}\" y=\"{}\" font-size=\"11\" text-anchor=\"end\">{}</text>\n",
+                    left + SQUARE - 5,
+                    top + 14,
+                    tile.size()
+                ));
+            }
+        }
+
+        match &options.last_turn {
+            Some(Turn::Place { pos, .. }) => {
+                let (left, top) = corner(*pos);
+                svg.push_str(&format!(
+                    "<rect class=\"last-turn\" x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" fill=\"none\" \
+                     stroke=\"{HIGHLIGHT}\" stroke-width=\"3\"/>\n",
+                    left + 2,
+                    top + 2,
+                    SQUARE - 4,
+                    SQUARE - 4
+                ));
+            }
+            Some(Turn::Move {
+                pos,
+                direction,
+                moves,
+            }) => {
+                // the first drop is one step away and every step after the
+                // last drop is ignored
+                let steps = 1 + moves.iter().rev().skip(1).filter(|&&step| step).count();
+                let end = (0..steps)
+                    .try_fold(*pos, |square, _| square.step(*direction))
+                    .unwrap_or(*pos);
+                let ((x1, y1), (x2, y2)) = (centre(*pos), centre(end));
+                svg.push_str(&format!(
+                    "<line class=\"last-turn\" x1=\"{x1}\" y1=\"{y1}\" x2=\"{x2}\" y2=\"{y2}\" \
+                     stroke=\"{HIGHLIGHT}\" stroke-width=\"5\" stroke-opacity=\"0.8\" \
+                     marker-end=\"url(#arrow)\"/>\n"
+                ));
+            }
+            None => {}
+        }
+
+        // reserves
+        for (i, colour) in [Colour::White, Colour::Black].into_iter().enumerate() {
+            let (stones, caps) = self.reserves(colour);
+            let y = PADDING + board + MARGIN + 14 + i * 22;
+            svg.push_str(&format!(
+                "<circle cx=\"{}\" cy=\"{}\" r=\"6\" fill=\"{}\" stroke=\"{DARK}\"/>\n",
+                MARGIN + 6,
+                y - 5,
+                fill(colour)
+            ));
+            svg.push_str(&format!(
+                "<text class=\"reserves\" x=\"{}\" y=\"{y}\" font-size=\"14\">{colour}: {stones} {}, {caps} \
+                 {}{}</text>\n",
+                MARGIN + 18,
+                if stones == 1 { "stone" } else { "stones" },
+                if caps == 1 { "capstone" } else { "capstones" },
+                if colour == self.to_move { " (to move)" } else { "" }
+            ));
+        }
+
+        svg.push_str("</svg>\n");
+        svg
+    }
+}
+
+fn fill(colour: Colour) -> &'static str {
+    match colour {
+        Colour::White => LIGHT,
+        Colour::Black => DARK,
+    }
+}
@@ -1,7 +1,26 @@
-use crate::{board::Board, direction::Direction, game::Game, pos::Pos, tile::Tile, turn::Turn};
+use std::cmp::Ordering;
+
+use crate::{board::Board, direction::Direction, game::Game, pos::Pos, turn::Turn};
+
+/// Index of the transform which undoes each one.
+/// The rotations undo each other and the mirror images undo themselves.
+const INVERSES: [usize; 8] = [0, 3, 2, 1, 4, 5, 6, 7];
 
 pub trait Symmetry: Sized {
     fn symmetries(self) -> [Self; 8];
+
+    /// Apply the transform with this index in [`Symmetry::symmetries`].
+    fn symmetry(self, index: usize) -> Self {
+        self.symmetries()
+            .into_iter()
+            .nth(index)
+            .expect("there are 8 symmetries")
+    }
+
+    /// Undo the transform with this index in [`Symmetry::symmetries`].
+    fn inverse_symmetry(self, index: usize) -> Self {
+        self.symmetry(INVERSES[index])
+    }
 }
 
 impl<const N: usize> Symmetry for Pos<N> {
@@ -54,10 +73,7 @@ impl<const N: usize> Symmetry for Turn<N> {
     }
 }
 
-impl<const N: usize> Symmetry for Board<N>
-where
-    [[Option<Tile>; N]; N]: Default,
-{
+impl<const N: usize> Symmetry for Board<N> {
     fn symmetries(self) -> [Self; 8] {
         (0..8)
             .map(|i| {
@@ -65,7 +81,7 @@ where
                 for y in 0..N {
                     for x in 0..N {
                         let pos = Pos { x, y };
-                        board[pos.symmetries()[i]] = self[pos].clone();
+                        board.set(pos.symmetries()[i], self[pos]);
                     }
                 }
                 board
@@ -76,10 +92,7 @@ where
     }
 }
 
-impl<const N: usize> Symmetry for Game<N>
-where
-    [[Option<Tile>; N]; N]: Default,
-{
+impl<const N: usize> Symmetry for Game<N> {
     fn symmetries(self) -> [Self; 8] {
         [
             self.clone(),
@@ -98,3 +111,35 @@ where
         })
     }
 }
+
+impl<const N: usize> Game<N> {
+    /// Pick one of the 8 symmetric variants of the position, so that they
+    /// can be treated as one. This is the smallest board when comparing
+    /// the squares in the order a1, b1, ..., a2, b2, ..., with empty
+    /// squares first. Also returns the index of the transform which makes
+    /// it, so turns in the canonical position can be brought back with
+    /// [`Symmetry::inverse_symmetry`].
+    pub fn canonical(&self) -> (Game<N>, usize) {
+        // the square at `pos` after transform `i`
+        let square = |i, pos: Pos<N>| self.board[pos.inverse_symmetry(i)];
+        let squares = || (0..N).flat_map(|y| (0..N).map(move |x| Pos { x, y }));
+        let index = (0..8)
+            .min_by(|&a, &b| {
+                squares()
+                    .map(|pos| square(a, pos).cmp(&square(b, pos)))
+                    .find(|&order| order != Ordering::Equal)
+                    .unwrap_or(Ordering::Equal)
+            })
+            .unwrap();
+
+        let mut board = Board::default();
+        for pos in squares() {
+            board.set(pos.symmetry(index), self.board[pos]);
+        }
+        let game = Game {
+            board,
+            ..self.clone()
+        };
+        (game, index)
+    }
+}
@@ -0,0 +1,89 @@
+use crate::{
+    bitboard::Bitboard,
+    colour::Colour,
+    game::{Game, GameResult},
+    move_list::MoveList,
+    tile::Shape,
+    turn::Turn,
+};
+
+impl<const N: usize> Game<N> {
+    /// Get the empty squares which would complete a road for this colour
+    /// if it placed a flat there.
+    pub fn road_threats(&self, colour: Colour) -> Bitboard<N> {
+        let roads = self.board.road_pieces(colour);
+        // squares next to roads connected to the edge, or on the edge itself
+        let touching = |edge: Bitboard<N>| edge.flood_fill(roads).dilate() | edge;
+        let horizontal = touching(Bitboard::column(0)) & touching(Bitboard::column(N - 1));
+        let vertical = touching(Bitboard::row(0)) & touching(Bitboard::row(N - 1));
+        (horizontal | vertical) & !self.board.occupied()
+    }
+
+    /// Get the turns with which this colour would win by road on the spot.
+    /// Works for either colour, as if it was their turn.
+    pub fn winning_moves(&self, colour: Colour) -> Vec<Turn<N>> {
+        // no road can be made while placing the opponent's pieces
+        if self.swap() {
+            return Vec::new();
+        }
+
+        let (stones, caps) = match colour {
+            Colour::White => (self.white_stones, self.white_caps),
+            Colour::Black => (self.black_stones, self.black_caps),
+        };
+        let mut turns = Vec::new();
+        for pos in self.road_threats(colour).positions() {
+            if stones > 0 {
+                turns.push(Turn::Place {
+                    pos,
+                    shape: Shape::Flat,
+                });
+            }
+            if caps > 0 {
+                turns.push(Turn::Place {
+                    pos,
+                    shape: Shape::Capstone,
+                });
+            }
+        }
+
+        let mut game = self.clone();
+        game.to_move = colour;
+        let mut spreads = MoveList::new();
+        game.generate_spreads(&mut spreads);
+        for turn in &spreads {
+            let undo = game
+                .play_undoable(turn.clone())
+                .expect("possible turns should be legal");
+            if game.winner() == (GameResult::Winner { colour, road: true }) {
+                turns.push(undo.turn().clone());
+            }
+            game.undo(undo);
+        }
+        turns
+    }
+
+    /// Get the turns with which this colour would win on flats on the spot,
+    /// by filling the board or placing its last piece. Works for either
+    /// colour, as if it was their turn.
+    pub fn flat_winning_moves(&self, colour: Colour) -> Vec<Turn<N>> {
+        // a turn fills at most one square by placing, or N - 1 by spreading
+        let (stones, caps) = self.reserves(colour);
+        let empty = N * N - self.board.occupied().count() as usize;
+        if self.swap() || (stones as usize + caps as usize > 1 && empty >= N) {
+            return Vec::new();
+        }
+
+        let mut game = self.clone();
+        game.to_move = colour;
+        let mut turns = Vec::new();
+        for turn in game.possible_turns() {
+            let undo = game.play_undoable(turn).expect("possible turns should be legal");
+            if game.winner() == (GameResult::Winner { colour, road: false }) {
+                turns.push(undo.turn().clone());
+            }
+            game.undo(undo);
+        }
+        turns
+    }
+}
@@ -1,33 +1,105 @@
-use std::iter::once;
-
 use arrayvec::ArrayVec;
 
-use crate::{colour::Colour, StrResult};
+use crate::{
+    colour::Colour,
+    error::{Error, TakResult},
+};
 
-#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub enum Shape {
     Flat,
     Wall,
     Capstone,
 }
 
-#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct Piece {
     pub colour: Colour,
     pub shape: Shape,
 }
 
-#[derive(Clone, Debug, PartialEq, Eq)]
+/// Colours of the pieces under the top of a tile, ordered bottom to top.
+/// Packed into one bit per piece (set for black) so that tiles are `Copy`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Stack {
+    bits: u128,
+    len: u8,
+}
+
+impl Stack {
+    /// The most pieces a stack can hold below the top of a tile.
+    pub const CAPACITY: usize = u128::BITS as usize;
+
+    pub const fn new() -> Self {
+        Stack { bits: 0, len: 0 }
+    }
+
+    pub const fn len(&self) -> usize {
+        self.len as usize
+    }
+
+    pub const fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Get the colour of the piece at `index`, counting from the bottom.
+    pub fn get(&self, index: usize) -> Option<Colour> {
+        (index < self.len()).then(|| Self::colour_of(self.bits >> index))
+    }
+
+    /// Put a piece of this colour on top of the stack.
+    pub fn push(&mut self, colour: Colour) {
+        assert!(self.len() < Self::CAPACITY, "stack is too tall to pack");
+        if colour == Colour::Black {
+            self.bits |= 1 << self.len;
+        }
+        self.len += 1;
+    }
+
+    /// Keep only the bottom `len` pieces.
+    pub fn truncate(&mut self, len: usize) {
+        if len < self.len() {
+            self.bits &= (1 << len) - 1;
+            self.len = len as u8;
+        }
+    }
+
+    /// Iterate over the colours from bottom to top.
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = Colour> + ExactSizeIterator {
+        let bits = self.bits;
+        (0..self.len()).map(move |i| Self::colour_of(bits >> i))
+    }
+
+    fn colour_of(bits: u128) -> Colour {
+        if bits & 1 == 0 {
+            Colour::White
+        } else {
+            Colour::Black
+        }
+    }
+}
+
+impl FromIterator<Colour> for Stack {
+    fn from_iter<T: IntoIterator<Item = Colour>>(iter: T) -> Self {
+        let mut stack = Stack::new();
+        for colour in iter {
+            stack.push(colour);
+        }
+        stack
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
 pub struct Tile {
     pub top: Piece,
-    pub stack: Vec<Colour>,
+    pub stack: Stack,
 }
 
 impl Tile {
     pub fn new(top: Piece) -> Self {
         Tile {
             top,
-            stack: Vec::new(),
+            stack: Stack::new(),
         }
     }
 
@@ -37,7 +109,7 @@ impl Tile {
     }
 
     /// Try to stack the piece on this tile.
-    pub fn stack(mut self, piece: Piece) -> StrResult<Self> {
+    pub fn stack(mut self, piece: Piece) -> TakResult<Self> {
         // Only allow stacking on top of flats, or flattening walls.
         match self.top.shape {
             Shape::Flat => Ok(()),
@@ -45,10 +117,10 @@ impl Tile {
                 if matches!(piece.shape, Shape::Capstone) {
                     Ok(())
                 } else {
-                    Err("can only flatten a wall with a capstone")
+                    Err(Error::CannotFlattenWall)
                 }
             }
-            Shape::Capstone => Err("cannot create a stack on top of a capstone"),
+            Shape::Capstone => Err(Error::CannotStackOnCapstone),
         }?;
 
         self.stack.push(self.top.colour);
@@ -60,33 +132,35 @@ impl Tile {
 
     /// Try taking the top `amount` pieces from this tile.
     /// Returned ArrayVec is ordered top to bottom.
-    pub fn take<const N: usize>(self, amount: usize) -> StrResult<(Option<Tile>, ArrayVec<Piece, N>)> {
+    pub fn take<const N: usize>(self, amount: usize) -> TakResult<(Option<Tile>, ArrayVec<Piece, N>)> {
         let count = self.size();
         if amount == 0 {
-            return Err("cannot take 0 from a tile".to_string());
+            return Err(Error::EmptyCarry);
         } else if amount > N {
-            return Err(format!("cannot take more than the carry limit, amount={amount}"));
+            return Err(Error::CarryLimitExceeded { amount, limit: N });
         } else if amount > count {
-            return Err(format!(
-                "cannot take more pieces than there are on the tile, amount={amount}, count={count}"
-            ));
+            return Err(Error::StackTooSmall { amount, size: count });
         }
 
-        let mut stack = self
-            .stack
-            .into_iter()
-            .map(|colour| Piece {
-                colour,
-                shape: Shape::Flat,
-            })
-            .chain(once(self.top))
-            .rev();
+        let mut carry = ArrayVec::new();
+        carry.push(self.top);
+        carry.extend(self.stack.iter().rev().take(amount - 1).map(|colour| Piece {
+            colour,
+            shape: Shape::Flat,
+        }));
 
-        let carry = stack.by_ref().take(amount).collect();
-
-        let left = stack.next().map(|top| Tile {
-            top,
-            stack: stack.rev().map(|p| p.colour).collect(),
+        let left = (amount < count).then(|| {
+            let mut stack = self.stack;
+            let height = count - amount - 1;
+            let colour = stack.get(height).unwrap();
+            stack.truncate(height);
+            Tile {
+                top: Piece {
+                    colour,
+                    shape: Shape::Flat,
+                },
+                stack,
+            }
         });
         Ok((left, carry))
     }
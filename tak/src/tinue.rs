@@ -0,0 +1,94 @@
+use crate::{
+    colour::Colour,
+    game::{Game, GameResult},
+    turn::Turn,
+};
+
+impl<const N: usize> Game<N> {
+    /// Search for a forced road win for this colour within `depth` of its
+    /// own turns, counting the one which completes the road.
+    ///
+    /// The attacker only tries turns which threaten to win right away,
+    /// while every reply of the defender is checked. Returns the winning
+    /// line, where the defender plays the reply which lasts the longest.
+    /// When it is the defender's turn the line starts with their reply.
+    /// `None` means there is no tinue within `depth`.
+    pub fn tinue(&self, colour: Colour, depth: usize) -> Option<Vec<Turn<N>>> {
+        self.tinue_with_budget(colour, depth, usize::MAX)
+    }
+
+    /// Like [`Game::tinue`], but gives up after searching `nodes` positions
+    /// of the attacker, in which case it returns `None` as well. A line
+    /// which is returned is always a forced win.
+    pub fn tinue_with_budget(&self, colour: Colour, depth: usize, mut nodes: usize) -> Option<Vec<Turn<N>>> {
+        let mut game = self.clone();
+        let mut line = if game.to_move == colour {
+            attack(&mut game, colour, depth, &mut nodes)?
+        } else {
+            defend(&mut game, colour, depth, &mut nodes)?
+        };
+        line.reverse();
+        Some(line)
+    }
+}
+
+/// The attacker is to move. Lines are built in reverse. Running out of
+/// nodes counts as no win, so it can only make the search miss a tinue.
+fn attack<const N: usize>(
+    game: &mut Game<N>,
+    attacker: Colour,
+    depth: usize,
+    nodes: &mut usize,
+) -> Option<Vec<Turn<N>>> {
+    if depth == 0 || *nodes == 0 {
+        return None;
+    }
+    *nodes -= 1;
+    if let Some(win) = game.winning_moves(attacker).into_iter().next() {
+        return Some(vec![win]);
+    }
+    if depth == 1 {
+        return None;
+    }
+
+    for turn in game.possible_turns() {
+        let undo = game.play_undoable(turn).expect("possible turns should be legal");
+        let line = (game.winner() == GameResult::Ongoing && !game.winning_moves(attacker).is_empty())
+            .then(|| defend(game, attacker, depth - 1, nodes))
+            .flatten();
+        let turn = undo.turn().clone();
+        game.undo(undo);
+        if let Some(mut line) = line {
+            line.push(turn);
+            return Some(line);
+        }
+    }
+    None
+}
+
+/// The defender is to move. Lines are built in reverse.
+fn defend<const N: usize>(
+    game: &mut Game<N>,
+    attacker: Colour,
+    depth: usize,
+    nodes: &mut usize,
+) -> Option<Vec<Turn<N>>> {
+    let mut longest: Option<Vec<Turn<N>>> = None;
+    for turn in game.possible_turns() {
+        let undo = game.play_undoable(turn).expect("possible turns should be legal");
+        let line = match game.winner() {
+            GameResult::Winner { colour, .. } if colour == attacker => Some(Vec::new()),
+            GameResult::Ongoing => attack(game, attacker, depth, nodes),
+            _ => None,
+        };
+        let turn = undo.turn().clone();
+        game.undo(undo);
+
+        let mut line = line?;
+        if !matches!(&longest, Some(longest) if longest.len() > line.len()) {
+            line.push(turn);
+            longest = Some(line);
+        }
+    }
+    longest
+}
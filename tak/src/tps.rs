@@ -3,20 +3,20 @@ use regex::Regex;
 use crate::{
     board::Board,
     colour::Colour,
-    game::Game,
+    error::{Error, TakResult},
+    game::{Capstones, Game, Rules, Stones},
     pos::Pos,
     ptn::{FromPTN, ToPTN},
-    tile::{Piece, Shape, Tile},
-    StrResult,
+    tile::{Piece, Shape, Stack, Tile},
 };
 
 lazy_static! {
-    static ref EMPTY_TILE_RE: Regex = Regex::new("x([0-9]?)").unwrap();
-    static ref STACK_TILE_RE: Regex = Regex::new("([12]*)([12])([CS]?)").unwrap();
+    static ref EMPTY_TILE_RE: Regex = Regex::new("^x([1-9][0-9]*)?$").unwrap();
+    static ref STACK_TILE_RE: Regex = Regex::new("^([12]*)([12])([CS]?)$").unwrap();
 }
 
 pub trait FromTPS: Sized {
-    fn from_tps(s: &str) -> StrResult<Self>;
+    fn from_tps(s: &str) -> TakResult<Self>;
 }
 
 pub trait ToTPS {
@@ -26,9 +26,10 @@ pub trait ToTPS {
 impl<const N: usize> ToTPS for Game<N> {
     /// Technically this is modified TPS with extra info
     fn to_tps(&self) -> String {
-        // TPS to_move move_num (white_reserves) (black_reserves)
+        // TPS to_move move_num (white_reserves) (black_reserves) komi turn_limit
+        // opening
         format!(
-            "{} {} {} ({}/{}) ({}/{}) {}",
+            "{} {} {} ({}/{}) ({}/{}) {} {} {}",
             self.board.to_tps(),
             self.to_move.to_ptn(),
             (self.ply / 2) + 1,
@@ -36,11 +37,182 @@ impl<const N: usize> ToTPS for Game<N> {
             self.white_caps,
             self.black_stones,
             self.black_caps,
-            self.komi
+            self.rules.komi,
+            turn_limit_ptn(self.rules.turn_limit),
+            opening_ptn(self.rules.swap)
         )
     }
 }
 
+impl<const N: usize> FromTPS for Game<N> {
+    /// Accepts standard TPS, in which case the reserves are inferred from
+    /// the pieces on the board, and the extended form made by
+    /// [`ToTPS`] with reserves, komi, turn limit and opening. Other rules
+    /// are the standard ones for the size.
+    fn from_tps(s: &str) -> TakResult<Self> {
+        game_from_tps(s, Game::<N>::standard_rules())
+    }
+}
+
+/// Parse TPS for a game played with these rules. The rules in the extended
+/// form replace the ones given. Extended TPS from before the turn limit and
+/// opening were written, with only the komi, is still accepted.
+pub(crate) fn game_from_tps<const N: usize>(s: &str, mut rules: Rules) -> TakResult<Game<N>> {
+    rules.check()?;
+    let fields: Vec<_> = s
+        .split_whitespace()
+        .map(|field| (field.as_ptr() as usize - s.as_ptr() as usize, field))
+        .collect();
+    if !matches!(fields.len(), 3 | 6 | 8) {
+        return Err(Error::parse(
+            s,
+            0,
+            format!("expected 3, 6 or 8 fields in TPS, got {}", fields.len()),
+        ));
+    }
+    let err = |(offset, _): (usize, &str), message: &str| Error::parse(s, offset, message);
+
+    let board = Board::from_tps(fields[0].1).map_err(|err| match err {
+        Error::ParseError {
+            position, message, ..
+        } => Error::parse(s, fields[0].0 + position, message),
+        err => err,
+    })?;
+    let to_move = Colour::from_ptn(fields[1].1).map_err(|_| err(fields[1], "unknown player to move"))?;
+    let move_number = fields[2]
+        .1
+        .parse::<u64>()
+        .ok()
+        .filter(|&n| n > 0)
+        .ok_or_else(|| err(fields[2], "cannot parse move number"))?;
+    let ply = (move_number - 1)
+        .checked_mul(2)
+        .and_then(|ply| {
+            ply.checked_add(match to_move {
+                Colour::White => 0,
+                Colour::Black => 1,
+            })
+        })
+        .ok_or_else(|| err(fields[2], "move number is too large"))?;
+
+    let (white_stones, white_caps, black_stones, black_caps);
+    if fields.len() == 8 {
+        rules.turn_limit =
+            parse_turn_limit(fields[6].1).ok_or_else(|| err(fields[6], "cannot parse turn limit"))?;
+        rules.swap = parse_opening(fields[7].1).ok_or_else(|| err(fields[7], "unknown opening"))?;
+    }
+    if fields.len() >= 6 {
+        // the reserves and the pieces on the board have to add up to the rules
+        let reserves = |colour, field: (usize, &str)| {
+            let (stones, caps) =
+                parse_reserves(field.1).ok_or_else(|| err(field, "cannot parse reserves"))?;
+            let (used_stones, used_caps) = board.piece_counts(colour);
+            if used_stones + stones as usize != rules.stones as usize
+                || used_caps + caps as usize != rules.caps as usize
+            {
+                return Err(err(field, "reserves do not add up to the rules"));
+            }
+            Ok((stones, caps))
+        };
+        (white_stones, white_caps) = reserves(Colour::White, fields[3])?;
+        (black_stones, black_caps) = reserves(Colour::Black, fields[4])?;
+        rules.komi = fields[5]
+            .1
+            .parse()
+            .map_err(|_| err(fields[5], "cannot parse komi"))?;
+    } else {
+        let reserves = |colour| {
+            let (used_stones, used_caps) = board.piece_counts(colour);
+            let stones = (rules.stones as usize).checked_sub(used_stones);
+            let caps = (rules.caps as usize).checked_sub(used_caps);
+            stones.zip(caps).map(|(s, c)| (s as Stones, c as Capstones))
+        };
+        (white_stones, white_caps) =
+            reserves(Colour::White).ok_or_else(|| err(fields[0], "too many white pieces on the board"))?;
+        (black_stones, black_caps) =
+            reserves(Colour::Black).ok_or_else(|| err(fields[0], "too many black pieces on the board"))?;
+    }
+
+    Ok(Game {
+        board,
+        to_move,
+        ply,
+        white_stones,
+        black_stones,
+        white_caps,
+        black_caps,
+        rules,
+    })
+}
+
+/// Write a turn limit as a number of plies, or `none`.
+pub(crate) fn turn_limit_ptn(turn_limit: Option<u64>) -> String {
+    turn_limit.map_or_else(|| "none".to_string(), |limit| limit.to_string())
+}
+
+pub(crate) fn parse_turn_limit(s: &str) -> Option<Option<u64>> {
+    match s {
+        "none" => Some(None),
+        _ => s.parse().ok().map(Some),
+    }
+}
+
+/// Write whether the opening is played with swap, like PTN Ninja.
+pub(crate) fn opening_ptn(swap: bool) -> &'static str {
+    if swap {
+        "swap"
+    } else {
+        "no-swap"
+    }
+}
+
+pub(crate) fn parse_opening(s: &str) -> Option<bool> {
+    match s {
+        "swap" => Some(true),
+        "no-swap" => Some(false),
+        _ => None,
+    }
+}
+
+/// Parse reserves written like `(21/1)`.
+fn parse_reserves(s: &str) -> Option<(Stones, Capstones)> {
+    let (stones, caps) = s.strip_prefix('(')?.strip_suffix(')')?.split_once('/')?;
+    Some((stones.parse().ok()?, caps.parse().ok()?))
+}
+
+impl ToTPS for Tile {
+    /// Get the TPS of one square, like `12C`.
+    fn to_tps(&self) -> String {
+        let mut out: String = self.stack.iter().map(|colour| colour.to_ptn()).collect();
+        out.push_str(&self.top.colour.to_ptn());
+        out.push_str(&self.top.shape.to_ptn());
+        out
+    }
+}
+
+impl FromTPS for Tile {
+    fn from_tps(s: &str) -> TakResult<Self> {
+        let colours = s.strip_suffix(['S', 'C']).unwrap_or(s);
+        let shape = Shape::from_ptn(&s[colours.len()..])?;
+        let mut stack = colours
+            .char_indices()
+            .map(|(i, c)| match c {
+                '1' => Ok(Colour::White),
+                '2' => Ok(Colour::Black),
+                _ => Err(Error::parse(s, i, "expected a colour")),
+            })
+            .collect::<TakResult<Vec<_>>>()?;
+        let colour = stack.pop().ok_or_else(|| Error::parse(s, 0, "empty tile"))?;
+        if stack.len() > Stack::CAPACITY {
+            return Err(Error::parse(s, 0, "stack is too tall"));
+        }
+        Ok(Tile {
+            top: Piece { colour, shape },
+            stack: stack.into_iter().collect(),
+        })
+    }
+}
+
 impl<const N: usize> ToTPS for Board<N> {
     /// Get board TPS
     fn to_tps(&self) -> String {
@@ -66,11 +238,7 @@ impl<const N: usize> ToTPS for Board<N> {
                 let pos = Pos { x, y };
                 if let Some(tile) = &self[pos] {
                     empty = add_empty(&mut out, empty);
-                    for colour in &tile.stack {
-                        out.push_str(&colour.to_ptn());
-                    }
-                    out.push_str(&tile.top.colour.to_ptn());
-                    out.push_str(&tile.top.shape.to_ptn());
+                    out.push_str(&tile.to_tps());
                     out.push(',');
                 } else {
                     empty += 1;
@@ -85,41 +253,61 @@ impl<const N: usize> ToTPS for Board<N> {
     }
 }
 
-impl<const N: usize> FromTPS for Board<N>
-where
-    [[Option<Tile>; N]; N]: Default,
-{
-    fn from_tps(s: &str) -> StrResult<Self> {
+impl<const N: usize> FromTPS for Board<N> {
+    fn from_tps(s: &str) -> TakResult<Self> {
         let mut board = Board::default();
         let row_count = s.split('/').count();
         if row_count != N {
-            return Err(format!("expected {N} rows, got {row_count}"));
+            return Err(Error::parse(s, 0, format!("expected {N} rows, got {row_count}")));
         }
+        let mut offset = 0;
         for (i, row) in s.split('/').enumerate() {
             let y = N - i - 1;
             let mut x = 0;
             for tile in row.split(',') {
+                if x >= N {
+                    return Err(Error::parse(
+                        s,
+                        offset,
+                        format!("too many tiles in row number {}", y + 1),
+                    ));
+                }
                 if let Some(cap) = EMPTY_TILE_RE.captures(tile) {
-                    x += cap[1].parse::<usize>().unwrap_or(1);
+                    let run = match cap.get(1) {
+                        Some(run) => run
+                            .as_str()
+                            .parse::<usize>()
+                            .map_err(|_| Error::parse(s, offset, "too many empty tiles"))?,
+                        None => 1,
+                    };
+                    x = x.saturating_add(run);
                 } else {
                     let pos = Pos { x, y };
                     let cap = STACK_TILE_RE
                         .captures(tile)
-                        .ok_or_else(|| format!("didn't recognize stack {tile}"))?;
+                        .ok_or_else(|| Error::parse(s, offset, "didn't recognize stack"))?;
+                    if cap[1].len() > Stack::CAPACITY {
+                        return Err(Error::parse(s, offset, "stack is too tall"));
+                    }
                     let stack = cap[1]
                         .chars()
                         .map(|c| Colour::from_ptn(&c.to_string()))
-                        .collect::<StrResult<Vec<_>>>()?;
+                        .collect::<TakResult<Stack>>()?;
                     let piece = Piece {
                         shape: Shape::from_ptn(&cap[3])?,
                         colour: Colour::from_ptn(&cap[2])?,
                     };
-                    board[pos] = Some(Tile { top: piece, stack });
+                    board.set(pos, Some(Tile { top: piece, stack }));
                     x += 1;
                 }
+                offset += tile.len() + 1;
             }
             if x != N {
-                return Err(format!("only got {x} tiles in row number {y}, expected {N}"));
+                return Err(Error::parse(
+                    s,
+                    offset - 1,
+                    format!("got {x} tiles in row number {}, expected {N}", y + 1),
+                ));
             }
         }
         Ok(board)
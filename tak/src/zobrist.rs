@@ -0,0 +1,169 @@
+use std::hash::{Hash, Hasher};
+
+use crate::{
+    board::Board,
+    colour::Colour,
+    game::Game,
+    pos::Pos,
+    tile::{Shape, Stack, Tile},
+};
+
+/// How many pieces under the top of each stack are included in the hash
+/// kept by [`Board`] and [`Game`], see [`Zobrist`] for other depths.
+///
+/// Positions which only differ deeper down than this always share a key,
+/// so the hash is not a safe table key on its own once stacks get taller.
+/// Tables which need exact results have to store the position and compare
+/// it on every hit.
+pub const ZOBRIST_STACK_DEPTH: usize = 10;
+
+/// Computes Zobrist keys from scratch, hashing a chosen number of pieces
+/// under the top of each stack.
+///
+/// With the default depth of [`ZOBRIST_STACK_DEPTH`] the keys equal
+/// [`Board::hash`] and [`Game::hash`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Zobrist {
+    stack_depth: usize,
+}
+
+impl Zobrist {
+    /// Panics if the depth is more than [`Stack::CAPACITY`], which already
+    /// covers every piece of a stack.
+    pub const fn new(stack_depth: usize) -> Self {
+        assert!(stack_depth <= Stack::CAPACITY, "stack depth is too large");
+        Self { stack_depth }
+    }
+
+    pub const fn stack_depth(&self) -> usize {
+        self.stack_depth
+    }
+
+    /// Key of the board, see [`Board::hash`].
+    pub fn board<const N: usize>(&self, board: &Board<N>) -> u64 {
+        (0..N)
+            .flat_map(|y| (0..N).map(move |x| Pos { x, y }))
+            .fold(0, |hash, pos| hash ^ tile_hash(pos, board[pos], self.stack_depth))
+    }
+
+    /// Key of the position, see [`Game::hash`].
+    pub fn game<const N: usize>(&self, game: &Game<N>) -> u64 {
+        self.board(&game.board) ^ game.state_hash()
+    }
+}
+
+impl Default for Zobrist {
+    fn default() -> Self {
+        Self::new(ZOBRIST_STACK_DEPTH)
+    }
+}
+
+const MAX_SQUARES: usize = 64;
+
+struct Keys {
+    tops: [[u64; 6]; MAX_SQUARES],
+    stacks: [[[u64; 2]; Stack::CAPACITY]; MAX_SQUARES],
+    black_to_move: u64,
+}
+
+static KEYS: Keys = Keys::generate();
+
+impl Keys {
+    const fn generate() -> Self {
+        let mut keys = Keys {
+            tops: [[0; 6]; MAX_SQUARES],
+            stacks: [[[0; 2]; Stack::CAPACITY]; MAX_SQUARES],
+            black_to_move: 0,
+        };
+        let mut state: u64 = 0x5EED;
+        let mut square = 0;
+        while square < MAX_SQUARES {
+            let mut piece = 0;
+            while piece < 6 {
+                state = state.wrapping_add(GOLDEN_GAMMA);
+                keys.tops[square][piece] = mix(state);
+                piece += 1;
+            }
+            let mut depth = 0;
+            while depth < Stack::CAPACITY {
+                state = state.wrapping_add(GOLDEN_GAMMA);
+                keys.stacks[square][depth][0] = mix(state);
+                state = state.wrapping_add(GOLDEN_GAMMA);
+                keys.stacks[square][depth][1] = mix(state);
+                depth += 1;
+            }
+            square += 1;
+        }
+        keys.black_to_move = mix(state.wrapping_add(GOLDEN_GAMMA));
+        keys
+    }
+}
+
+const GOLDEN_GAMMA: u64 = 0x9E37_79B9_7F4A_7C15;
+
+/// SplitMix64 finalizer.
+const fn mix(mut z: u64) -> u64 {
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^ (z >> 31)
+}
+
+const fn colour_index(colour: Colour) -> usize {
+    match colour {
+        Colour::White => 0,
+        Colour::Black => 1,
+    }
+}
+
+/// Key for the contents of one square, hashing up to `stack_depth` pieces
+/// under the top.
+pub(crate) fn tile_hash<const N: usize>(pos: Pos<N>, tile: Option<Tile>, stack_depth: usize) -> u64 {
+    let square = pos.y * N + pos.x;
+    match tile {
+        None => 0,
+        Some(tile) => {
+            let shape = match tile.top.shape {
+                Shape::Flat => 0,
+                Shape::Wall => 1,
+                Shape::Capstone => 2,
+            };
+            let top = KEYS.tops[square][2 * shape + colour_index(tile.top.colour)];
+            tile.stack
+                .iter()
+                .rev()
+                .take(stack_depth)
+                .enumerate()
+                .fold(top, |hash, (depth, colour)| {
+                    hash ^ KEYS.stacks[square][depth][colour_index(colour)]
+                })
+        }
+    }
+}
+
+/// Key for everything outside of the board.
+pub(crate) fn state_hash(
+    to_move: Colour,
+    (white_stones, white_caps): (u8, u8),
+    (black_stones, black_caps): (u8, u8),
+    half_komi: i32,
+) -> u64 {
+    let side = match to_move {
+        Colour::White => 0,
+        Colour::Black => KEYS.black_to_move,
+    };
+    let counts = u64::from_le_bytes([white_stones, white_caps, black_stones, black_caps, 0, 0, 0, 0]);
+    side ^ mix(counts ^ ((half_komi as u32 as u64) << 32))
+}
+
+/// A position as the key of a hash table which has to be exact. The
+/// Zobrist key only picks the bucket, and the whole position, ply included,
+/// is compared on every hit.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) struct TableKey<const N: usize>(pub Game<N>);
+
+impl<const N: usize> Hash for TableKey<N> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        state.write_u64(self.0.hash());
+        state.write_u64(self.0.ply);
+    }
+}
@@ -0,0 +1,100 @@
+use tak::*;
+
+#[test]
+fn bitboards_match_tiles() -> TakResult<()> {
+    let mut game = Game::<6>::default();
+    for _ in 0..200 {
+        if game.winner() != GameResult::Ongoing {
+            break;
+        }
+        game.nth_move(7_919_000_111)?;
+
+        let mut flat_diff = 0;
+        for y in 0..6 {
+            for x in 0..6 {
+                let pos = Pos { x, y };
+                let board = &game.board;
+                match board[pos] {
+                    Some(tile) => {
+                        assert!(board.pieces(tile.top.colour).contains(pos));
+                        assert!(!board.pieces(tile.top.colour.next()).contains(pos));
+                        assert_eq!(board.walls().contains(pos), tile.top.shape == Shape::Wall);
+                        assert_eq!(board.capstones().contains(pos), tile.top.shape == Shape::Capstone);
+                        if tile.top.shape == Shape::Flat {
+                            flat_diff += if tile.top.colour == Colour::White { 1 } else { -1 };
+                        }
+                    }
+                    None => assert!(!board.occupied().contains(pos)),
+                }
+            }
+        }
+        assert_eq!(game.board.flat_diff(), flat_diff);
+    }
+    Ok(())
+}
+
+#[test]
+fn take_from_stack() -> TakResult<()> {
+    let board = Board::<5>::from_tps("x5/x5/x5/x5/1221C,x4")?;
+    let tile = board[Pos { x: 0, y: 0 }].unwrap();
+    assert_eq!(tile.size(), 4);
+
+    let (left, carry) = tile.take::<5>(3)?;
+    assert_eq!(carry.as_slice(), &[
+        Piece {
+            colour: Colour::White,
+            shape: Shape::Capstone,
+        },
+        Piece {
+            colour: Colour::Black,
+            shape: Shape::Flat,
+        },
+        Piece {
+            colour: Colour::Black,
+            shape: Shape::Flat,
+        },
+    ]);
+    assert_eq!(
+        left,
+        Some(Tile::new(Piece {
+            colour: Colour::White,
+            shape: Shape::Flat,
+        }))
+    );
+
+    let (left, carry) = tile.take::<5>(4)?;
+    assert_eq!(left, None);
+    assert_eq!(carry.len(), 4);
+    Ok(())
+}
+
+#[test]
+fn dilate_stays_on_board() {
+    let corner = Bitboard::<5>::default().with(Pos { x: 4, y: 4 });
+    let dilated: Vec<_> = corner.dilate().positions().collect();
+    assert_eq!(dilated, [Pos { x: 4, y: 3 }, Pos { x: 3, y: 4 }, Pos {
+        x: 4,
+        y: 4
+    }]);
+
+    let edge = Bitboard::<5>::column(0);
+    assert_eq!(edge.dilate(), Bitboard::column(0) | Bitboard::column(1));
+    assert_eq!(
+        Bitboard::<5>::row(4).dilate(),
+        Bitboard::row(3) | Bitboard::row(4)
+    );
+}
+
+#[test]
+fn detailed_display() -> TakResult<()> {
+    let board = Board::<3>::from_tps("x3/x,1212S,x/2,x,12C")?;
+    assert_eq!(
+        board.detailed().to_string(),
+        "  a     b     c\n1 _b    .     wob\n2 .     wbw/b .\n3 .     .     .\n"
+    );
+    assert_eq!(
+        Board::<3>::default().detailed().to_string(),
+        "  a  b  c\n1 .  .  .\n2 .  .  .\n3 .  .  .\n"
+    );
+    Ok(())
+}
@@ -0,0 +1,126 @@
+use tak::*;
+
+const WHITE_FLAT: Piece = Piece {
+    colour: Colour::White,
+    shape: Shape::Flat,
+};
+const BLACK_FLAT: Piece = Piece {
+    colour: Colour::Black,
+    shape: Shape::Flat,
+};
+const BLACK_CAP: Piece = Piece {
+    colour: Colour::Black,
+    shape: Shape::Capstone,
+};
+
+#[test]
+fn build_position() -> TakResult<()> {
+    let game = GameBuilder::<5>::new()
+        .set_stack(Pos::from_ptn("a1")?, [
+            BLACK_FLAT, WHITE_FLAT, BLACK_FLAT, WHITE_FLAT,
+        ])
+        .place(Pos::from_ptn("e1")?, BLACK_FLAT)
+        .place(Pos::from_ptn("e1")?, BLACK_CAP)
+        .place(Pos::from_ptn("c3")?, WHITE_FLAT)
+        .to_move(Colour::Black)
+        .komi(Komi::from_half_points(5))
+        .build()
+        .unwrap();
+    assert_eq!(
+        game.to_tps(),
+        "x5/x5/x2,1,x2/x5/2121,x3,22C 2 2 (18/1) (18/0) 2.5 400 swap"
+    );
+    assert_eq!(game.ply, 3);
+    game.check_invariants().unwrap();
+
+    // the editor round trip keeps everything
+    assert_eq!(GameBuilder::from_game(&game).build(), Ok(game.clone()));
+    let mut builder = GameBuilder::from_game(&game);
+    assert_eq!(builder.remove(Pos::from_ptn("e1")?), Some(BLACK_CAP));
+    assert_eq!(
+        builder.build(),
+        Err(SetupError::ReservesMismatch {
+            colour: Colour::Black
+        })
+    );
+    let game = builder
+        .derive_reserves()
+        .ply(None)
+        .to_move(Colour::White)
+        .build()
+        .unwrap();
+    assert_eq!(
+        game.to_tps(),
+        "x5/x5/x2,1,x2/x5/2121,x3,2 1 2 (18/1) (18/1) 2.5 400 swap"
+    );
+    Ok(())
+}
+
+#[test]
+fn opening_positions() -> TakResult<()> {
+    assert_eq!(GameBuilder::<6>::new().build(), Ok(Game::default()));
+
+    let mut builder = GameBuilder::<6>::new();
+    builder
+        .place(Pos::from_ptn("a1")?, BLACK_FLAT)
+        .to_move(Colour::Black);
+    let game = builder.build().unwrap();
+    assert_eq!(game.ply, 1);
+    assert!(game.swap());
+
+    builder
+        .clear(Pos::from_ptn("a1")?)
+        .place(Pos::from_ptn("a1")?, WHITE_FLAT);
+    assert_eq!(builder.build(), Err(SetupError::OpeningPosition { ply: 1 }));
+
+    // without the swap any piece can come first
+    builder.rules(Rules {
+        swap: false,
+        ..Rules::standard(6).unwrap()
+    });
+    assert_eq!(builder.build().map(|game| game.ply), Ok(1));
+    Ok(())
+}
+
+#[test]
+fn illegal_setups() -> TakResult<()> {
+    let a1 = Pos::from_ptn("a1")?;
+    let mut builder = GameBuilder::<5>::new();
+    builder.set_stack(a1, [BLACK_CAP, WHITE_FLAT]);
+    assert_eq!(
+        builder.build(),
+        Err(SetupError::BuriedPiece {
+            square: "a1".to_string()
+        })
+    );
+
+    builder
+        .set_stack(a1, [WHITE_FLAT, BLACK_CAP])
+        .place(Pos::from_ptn("b1")?, BLACK_CAP);
+    assert_eq!(
+        builder.build(),
+        Err(SetupError::TooManyPieces {
+            colour: Colour::Black
+        })
+    );
+
+    builder.clear(Pos::from_ptn("b1")?).ply(4).to_move(Colour::Black);
+    assert_eq!(
+        builder.build(),
+        Err(SetupError::PlyParity {
+            ply: 4,
+            to_move: Colour::Black
+        })
+    );
+
+    builder
+        .ply(None)
+        .set_stack(Pos::from_ptn("e5")?, (0..30).map(|_| WHITE_FLAT));
+    assert_eq!(
+        builder.build(),
+        Err(SetupError::TooManyPieces {
+            colour: Colour::White
+        })
+    );
+    Ok(())
+}
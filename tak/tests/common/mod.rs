@@ -0,0 +1,14 @@
+use tak::*;
+
+/// Play out the line and check that this colour wins at the end of it,
+/// not before. Returns the result so the kind of win can be checked.
+pub fn check_line<const N: usize>(game: &Game<N>, line: &[Turn<N>], winner: Colour) -> TakResult<GameResult> {
+    let mut game = game.clone();
+    for turn in line {
+        assert_eq!(game.winner(), GameResult::Ongoing);
+        game.play(turn.clone())?;
+    }
+    let result = game.winner();
+    assert!(matches!(result, GameResult::Winner { colour, .. } if colour == winner));
+    Ok(result)
+}
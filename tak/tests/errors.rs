@@ -0,0 +1,71 @@
+use tak::*;
+
+#[test]
+fn illegal_turns() -> TakResult<()> {
+    let mut game = Game::<5>::from_ptn("1. a1 e1 2. Cc3 Sd3")?;
+    assert_eq!(
+        game.clone().play(Turn::from_ptn("a1")?),
+        Err(Error::OccupiedSquare {
+            square: "a1".to_string()
+        })
+    );
+    assert_eq!(
+        game.clone().play(Turn::from_ptn("Cb2")?),
+        Err(Error::NoCapstoneLeft)
+    );
+    assert_eq!(
+        game.clone().play(Turn::from_ptn("b1>")?),
+        Err(Error::EmptySquare {
+            square: "b1".to_string()
+        })
+    );
+    assert_eq!(
+        game.clone().play(Turn::from_ptn("d3<")?),
+        Err(Error::NotYourStack {
+            square: "d3".to_string()
+        })
+    );
+    assert_eq!(
+        game.clone().play(Turn::from_ptn("2c3>")?),
+        Err(Error::StackTooSmall { amount: 2, size: 1 })
+    );
+    assert_eq!(
+        game.clone().play(Turn::from_ptn("e1>")?),
+        Err(Error::MoveOffBoard {
+            square: "e1".to_string()
+        })
+    );
+
+    game.play_ptn_moves(&["d2", "b5", "c2", "a5"])?;
+    assert_eq!(
+        game.clone().play(Turn::from_ptn("d2+")?),
+        Err(Error::CannotFlattenWall)
+    );
+    assert_eq!(
+        game.clone().play(Turn::from_ptn("c2+")?),
+        Err(Error::CannotStackOnCapstone)
+    );
+    Ok(())
+}
+
+#[test]
+fn opening_placement() {
+    let mut game = Game::<5>::default();
+    assert_eq!(
+        game.play(Turn::from_ptn("Sa1").unwrap()),
+        Err(Error::OpeningPlacement { ply: 0 })
+    );
+}
+
+#[test]
+fn parse_errors() {
+    assert!(matches!(Turn::<5>::from_ptn("f1"), Err(Error::ParseError { .. })));
+    assert!(matches!(
+        Board::<5>::from_tps("x5/x5/x5/x5"),
+        Err(Error::ParseError { .. })
+    ));
+    assert!(matches!(
+        Board::<5>::from_tps("x5/x5/x5/x5/x3,3,x"),
+        Err(Error::ParseError { position: 15, .. })
+    ));
+}
@@ -0,0 +1,79 @@
+use tak::*;
+
+/// Play random turns, checking the invariants after each one, and that the
+/// result does not change once the game is over.
+fn playout<const N: usize>(mut game: Game<N>, seed: usize) -> TakResult<()> {
+    game.check_invariants().unwrap();
+    while game.winner() == GameResult::Ongoing {
+        let before = game.clone();
+        let undo = game.play_undoable(game.possible_turns()[seed % game.possible_turns().len()].clone())?;
+        game.check_invariants().unwrap();
+        let after = game.clone();
+        game.undo(undo);
+        assert_eq!(game, before);
+        game = after;
+    }
+    let result = game.winner();
+    // TPS alone does not hold custom reserves, PTN keeps them in the tags
+    let ptn = GameRecord::new(game).to_ptn();
+    assert_eq!(GameRecord::<N>::from_ptn(&ptn)?.game().winner(), result);
+    Ok(())
+}
+
+#[test]
+fn playouts_keep_invariants() -> TakResult<()> {
+    for seed in [1_000_000_007, 998_244_353, 9_576_890_767] {
+        playout(Game::<3>::default(), seed)?;
+        playout(Game::<5>::with_komi(Komi::from_half_points(5)), seed)?;
+        playout(Game::<8>::default(), seed)?;
+        playout(
+            Game::<6>::with_rules(Rules {
+                stones: 12,
+                caps: 2,
+                swap: false,
+                ..Rules::standard(6).unwrap()
+            })?,
+            seed,
+        )?;
+    }
+    Ok(())
+}
+
+#[test]
+fn positions_keep_invariants() -> TakResult<()> {
+    let game = Game::<5>::from_ptn("1. a1 e5 2. Cc3 d4 3. c3> Sb2 4. d3+ c3")?;
+    game.check_invariants().unwrap();
+    playout(game, 2_147_483_647)?;
+
+    let game = Game::<6>::from_tps("1,1,1,1,1,2/2,2,2,2,2,1/1,1,1,1,1,2/2,2,2,2,2,1/x6/x6 2 5")?;
+    game.check_invariants().unwrap();
+    playout(game, 2_147_483_647)
+}
+
+#[test]
+fn violations() -> TakResult<()> {
+    // reserves which do not add up
+    let mut game = Game::<5>::from_tps("x5/x5/x5/x5/1,x4 2 1 (20/1) (21/1) 0")?;
+    game.check_invariants().unwrap();
+    game.white_stones = 21;
+    assert_eq!(
+        game.check_invariants(),
+        Err(InvariantViolation::PieceCount {
+            colour: Colour::White,
+            stones: 22,
+            caps: 1,
+        })
+    );
+
+    let mut game = Game::<5>::from_tps("x5/x5/x5/x5/2,1,x3 1 2")?;
+    game.check_invariants().unwrap();
+    game.to_move = Colour::Black;
+    assert_eq!(
+        game.check_invariants(),
+        Err(InvariantViolation::PlyParity {
+            ply: 2,
+            to_move: Colour::Black,
+        })
+    );
+    Ok(())
+}
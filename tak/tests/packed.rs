@@ -0,0 +1,76 @@
+use tak::*;
+
+/// All turns in the order of the policy index, placements on an empty
+/// board and then spreads with tall stacks on every square.
+fn all_turns<const N: usize>() -> Vec<Turn<N>> {
+    let game = Game::<N> {
+        ply: 4,
+        ..Default::default()
+    };
+    let mut turns = game.possible_turns();
+
+    let mut board = Board::default();
+    for x in 0..N {
+        for y in 0..N {
+            board.set(
+                Pos { x, y },
+                Some(Tile {
+                    top: Piece {
+                        colour: Colour::White,
+                        shape: Shape::Flat,
+                    },
+                    stack: (0..N).map(|_| Colour::White).collect(),
+                }),
+            );
+        }
+    }
+    let game = Game {
+        board,
+        ply: 4,
+        ..Default::default()
+    };
+    turns.extend(game.possible_turns());
+    turns
+}
+
+fn check_size<const N: usize>(count: usize) {
+    let turns = all_turns::<N>();
+    assert_eq!(turns.len(), count);
+    assert_eq!(PackedTurn::<N>::COUNT, count);
+    for (i, turn) in turns.into_iter().enumerate() {
+        let packed = PackedTurn::from(&turn);
+        assert_eq!(PackedTurn::from_bits(packed.bits()), Some(packed));
+        assert_eq!(Turn::from(packed), turn);
+        assert_eq!(packed.index(), Some(i), "{turn:?}");
+        assert_eq!(PackedTurn::from_index(i), Some(packed));
+    }
+    assert_eq!(PackedTurn::<N>::from_index(count), None);
+}
+
+#[test]
+fn policy_index() {
+    check_size::<3>(126);
+    check_size::<4>(480);
+    check_size::<5>(1575);
+    check_size::<6>(4572);
+    check_size::<7>(12495);
+    check_size::<8>(32704);
+}
+
+#[test]
+fn invalid_bits() -> TakResult<()> {
+    // a3 is off the board on 2 rows, and so is a spread off the edge
+    let packed = PackedTurn::<5>::from(Turn::from_ptn("a3")?);
+    assert_eq!(PackedTurn::<2>::from_bits(packed.bits()), None);
+    let packed = PackedTurn::<5>::from(Turn::from_ptn("3a1>111")?);
+    assert_eq!(PackedTurn::<3>::from_bits(packed.bits()), None);
+    assert!(PackedTurn::<4>::from_bits(packed.bits()).is_some());
+    // a carry over the limit
+    let packed = PackedTurn::<5>::from(Turn::from_ptn("5a1+")?);
+    assert_eq!(PackedTurn::<4>::from_bits(packed.bits()), None);
+
+    // there are no capstones on 4x4
+    let cap = PackedTurn::<4>::from(Turn::from_ptn("Ca1")?);
+    assert_eq!(cap.index(), None);
+    Ok(())
+}
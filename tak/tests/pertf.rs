@@ -18,7 +18,7 @@ fn perf_count<const N: usize>(game: Game<N>, depth: usize) -> usize {
 }
 
 #[test]
-fn position1_perft() -> StrResult<()> {
+fn position1_perft() -> TakResult<()> {
     let mut game = Game::<5>::default();
     game.play_ptn_moves(&["d3", "c3", "c4", "1d3<", "1c4-", "Sc4"])?;
     assert_eq!(perf_count(game.clone(), 1), 87);
@@ -28,7 +28,7 @@ fn position1_perft() -> StrResult<()> {
 }
 
 #[test]
-fn position2_perft() -> StrResult<()> {
+fn position2_perft() -> TakResult<()> {
     let mut game = Game::<5>::default();
     game.play_ptn_moves(&[
         "c2", "c3", "d3", "b3", "c4", "1c2+", "1d3<", "1b3>", "1c4-", "Cc2", "a1", "1c2+", "a2",
@@ -40,7 +40,7 @@ fn position2_perft() -> StrResult<()> {
 }
 
 #[test]
-fn position3_perft() -> StrResult<()> {
+fn position3_perft() -> TakResult<()> {
     let mut game = Game::<5>::default();
     game.play_ptn_moves(&[
         "c4", "c2", "d2", "c3", "b2", "d3", "1d2+", "b3", "d2", "b4", "1c2+", "1b3>", "2d3<", "1c4-", "d4",
@@ -71,3 +71,79 @@ fn perft_6() {
     assert_eq!(perf_count(Game::<6>::default(), 4), 13_586_048);
     // assert_eq!(perf_count(Game::<6>::default(), 5), 1_253_506_520);
 }
+
+#[test]
+fn move_list_generators() -> TakResult<()> {
+    let mut game = Game::<5>::default();
+    game.play_ptn_moves(&[
+        "c2", "c3", "d3", "b3", "c4", "1c2+", "1d3<", "1b3>", "1c4-", "Cc2", "a1", "1c2+", "a2",
+    ])?;
+
+    let mut list = MoveList::new();
+    game.generate_turns(&mut list);
+    assert_eq!(list.to_vec(), game.possible_turns());
+
+    list.clear();
+    game.generate_placements(&mut list);
+    assert!(list.iter().all(|turn| matches!(turn, Turn::Place { .. })));
+    let placements = list.len();
+
+    list.clear();
+    game.generate_spreads(&mut list);
+    assert!(list.iter().all(|turn| matches!(turn, Turn::Move { .. })));
+    assert_eq!(placements + list.len(), 104);
+
+    // only the opponent's flats on the first ply
+    let mut list = MoveList::new();
+    let game = Game::<5>::default();
+    game.generate_spreads(&mut list);
+    game.generate_placements(&mut list);
+    assert_eq!(list.len(), 25);
+    Ok(())
+}
+
+#[test]
+fn library_perft() -> TakResult<()> {
+    let mut game = Game::<5>::default();
+    game.play_ptn_moves(&["d3", "c3", "c4", "1d3<", "1c4-", "Sc4"])?;
+    for transpositions in [false, true] {
+        assert_eq!(perft(&game, 0, transpositions), 1);
+        assert_eq!(perft(&game, 1, transpositions), 87);
+        assert_eq!(perft(&game, 3, transpositions), 461_800);
+        assert_eq!(perft(&Game::<6>::default(), 4, transpositions), 13_586_048);
+    }
+    Ok(())
+}
+
+#[test]
+fn divide_perft() -> TakResult<()> {
+    let mut game = Game::<5>::default();
+    game.play_ptn_moves(&[
+        "c2", "c3", "d3", "b3", "c4", "1c2+", "1d3<", "1b3>", "1c4-", "Cc2", "a1", "1c2+", "a2",
+    ])?;
+    let divide = perft_divide(&game, 2, true);
+    assert_eq!(
+        divide.iter().map(|(turn, _)| turn.clone()).collect::<Vec<_>>(),
+        game.possible_turns()
+    );
+    for (turn, count) in divide {
+        let mut next = game.clone();
+        next.play(turn)?;
+        assert_eq!(count, perf_count(next, 1) as u64);
+    }
+    assert!(perft_divide(&game, 0, false).is_empty());
+    Ok(())
+}
+
+#[test]
+fn perft_after_the_game() -> TakResult<()> {
+    // a finished game counts as one position at any depth
+    let game = Game::<5>::from_ptn("1. a1 e5 2. a2 b5 3. b2 c5 4. c2 d5 5. d2 a5 6. e2")?;
+    assert_ne!(game.winner(), GameResult::Ongoing);
+    for depth in 0..3 {
+        assert_eq!(perft(&game, depth, false), 1);
+        assert_eq!(perf_count(game.clone(), depth), 1);
+        assert!(perft_divide(&game, depth, true).is_empty());
+    }
+    Ok(())
+}
@@ -0,0 +1,89 @@
+use tak::*;
+
+#[test]
+fn playtak_notation() -> TakResult<()> {
+    for (ptn, playtak) in [
+        ("a1", "P A1"),
+        ("Sb3", "P B3 W"),
+        ("Ce5", "P E5 C"),
+        ("a1>", "M A1 B1 1"),
+        ("3c3+12", "M C3 C5 1 2"),
+        ("5e5<221", "M E5 B5 2 2 1"),
+        ("2d2-", "M D2 D1 2"),
+    ] {
+        let turn = Turn::<5>::from_ptn(ptn)?;
+        assert_eq!(turn.to_playtak(), playtak);
+        assert_eq!(Turn::from_playtak(playtak)?, turn);
+    }
+    Ok(())
+}
+
+#[test]
+fn playtak_consistency() -> TakResult<()> {
+    let mut game = Game::<6>::default();
+    for _ in 0..60 {
+        for turn in game.possible_turns() {
+            assert_eq!(Turn::from_playtak(&turn.to_playtak())?, turn);
+        }
+        game.nth_move(1_000_000_007)?;
+    }
+    Ok(())
+}
+
+#[test]
+fn playtak_errors() {
+    for (playtak, position) in [
+        ("", 0),
+        ("X A1", 0),
+        ("P a1", 2),
+        ("P F1", 2),
+        ("P A1 X", 5),
+        ("P A1 C W", 7),
+        ("M A1 B2 1", 5),
+        ("M A1 C1 2", 5),
+        ("M A1 B1 0", 8),
+        ("M A1 E1 2 2 1 1", 14),
+    ] {
+        match Turn::<5>::from_playtak(playtak) {
+            Err(Error::ParseError { position: p, .. }) => assert_eq!(p, position, "{playtak:?}"),
+            other => panic!("{playtak:?} gave {other:?}"),
+        }
+    }
+}
+
+#[cfg(feature = "takparse")]
+#[test]
+fn takparse_conversions() -> TakResult<()> {
+    fn check<const N: usize>(seed: usize) -> TakResult<()> {
+        let mut game = Game::<N>::default();
+        for _ in 0..(3 * N * N) {
+            if game.winner() != GameResult::Ongoing {
+                break;
+            }
+            for turn in game.possible_turns() {
+                let ptn = turn.to_ptn();
+                let m = takparse::Move::from(turn.clone());
+                assert_eq!(m.to_string(), ptn);
+                assert_eq!(ptn.parse::<takparse::Move>().ok(), Some(m), "{ptn}");
+                assert_eq!(Turn::<N>::try_from(m)?, turn);
+            }
+            game.nth_move(seed)?;
+        }
+        Ok(())
+    }
+    check::<3>(1_000_000_007)?;
+    check::<4>(998_244_353)?;
+    check::<5>(7_919_000_111)?;
+    check::<6>(1_000_000_007)?;
+    check::<7>(998_244_353)?;
+    check::<8>(7_919_000_111)
+}
+
+#[cfg(feature = "takparse")]
+#[test]
+fn takparse_out_of_bounds() {
+    let m: takparse::Move = "f1".parse().unwrap();
+    assert!(matches!(Turn::<5>::try_from(m), Err(Error::ParseError { .. })));
+    let m: takparse::Move = "6a1>".parse().unwrap();
+    assert!(matches!(Turn::<5>::try_from(m), Err(Error::ParseError { .. })));
+}
@@ -7,7 +7,7 @@ const PLIES: &[&str] = &[
 ];
 
 #[test]
-fn ptn_consistency() -> StrResult<()> {
+fn ptn_consistency() -> TakResult<()> {
     for ply in PLIES {
         let turn = Turn::<6>::from_ptn(ply)?;
         assert_eq!(turn, Turn::from_ptn(&turn.to_ptn())?);
@@ -16,7 +16,7 @@ fn ptn_consistency() -> StrResult<()> {
 }
 
 #[test]
-fn move_gen_ptn_consistency() -> StrResult<()> {
+fn move_gen_ptn_consistency() -> TakResult<()> {
     let game = Game::<6>::from_ptn(
         "1. c4 d4
         2. d3 Sc3
@@ -56,7 +56,7 @@ fn move_gen_ptn_consistency() -> StrResult<()> {
 }
 
 #[test]
-fn game_options() -> StrResult<()> {
+fn game_options() -> TakResult<()> {
     let game = Game::<6>::from_ptn(
         r#"
         [Site "ptn.ninja"]
@@ -77,3 +77,279 @@ fn game_options() -> StrResult<()> {
     });
     Ok(())
 }
+
+#[test]
+fn record_round_trip() -> TakResult<()> {
+    let mut record = GameRecord::new(Game::<5>::with_komi(2));
+    while record.game().winner() == GameResult::Ongoing {
+        let mut game = record.game().clone();
+        game.nth_move(1_000_000_007)?;
+        let turn =
+            record.game().possible_turns()[1_000_000_007 % record.game().possible_turns().len()].clone();
+        record.play(turn)?;
+        assert_eq!(record.game(), &game);
+    }
+    let ptn = record.to_ptn();
+    assert!(ptn.starts_with("[Size \"5\"]\n[Komi \"2\"]\n[Flats \"21\"]\n[Caps \"1\"]\n[Result "));
+    assert_eq!(GameRecord::from_ptn(&ptn)?, record);
+    Ok(())
+}
+
+#[test]
+fn record_from_position() -> TakResult<()> {
+    let start = Game::<6>::from_tps("1,1,1,1,1,2/2,2,2,2,2,1/1,1,1,1,1,2/2,2,2,2,2,1/x6/x6 2 5")?;
+    let mut record = GameRecord::new(start);
+    record.play(Turn::from_ptn("e2")?)?;
+    record.play(Turn::from_ptn("Cd2")?)?;
+
+    let ptn = record.to_ptn();
+    assert!(ptn.contains("[TPS \"1,1,1,1,1,2/2,2,2,2,2,1/1,1,1,1,1,2/2,2,2,2,2,1/x6/x6 2 5\"]\n"));
+    assert!(ptn.ends_with("5. -- e2\n6. Cd2\n"));
+    assert_eq!(GameRecord::from_ptn(&ptn)?, record);
+
+    assert_eq!(record.undo(), Some(Turn::from_ptn("Cd2")?));
+    assert_eq!(record.turns().count(), 1);
+    Ok(())
+}
+
+#[test]
+fn record_rejects_illegal_turns() -> TakResult<()> {
+    let mut record = GameRecord::new(Game::<5>::from_tps("x5/x5/x5/x5/x,21,x,2S,x 1 5")?);
+    record.play(Turn::from_ptn("a5")?)?;
+    record.play(Turn::from_ptn("e5")?)?;
+    let copy = record.clone();
+    // the spread drops a flat on c1 before reaching the wall on d1
+    assert_eq!(
+        record.play(Turn::from_ptn("2b1>11")?),
+        Err(Error::CannotFlattenWall)
+    );
+    assert_eq!(record, copy);
+    assert_eq!(record.turns().count(), 2);
+    Ok(())
+}
+
+const PLAYTAK_GAME: &str = r#"[Site "PlayTak.com"]
+[Event "Online Play"]
+[Date "2022.08.14"]
+[Time "19:02:11"]
+[Player1 "WilemBot"]
+[Player2 "Guest1234"]
+[Clock "10:0 +10"]
+[Result "R-0"]
+[Size "5"]
+[Komi "2"]
+[Flats "21"]
+[Caps "1"]
+
+{a friendly game}
+1. e5 a1
+2. b1 {center} a5?!
+3. c1 a4
+4. d1 a3 {threatening "b" file}
+5. Cc3 Cb4
+6. c3-*' a2
+7. e1" {road} R-0
+"#;
+
+#[test]
+fn ptn_game_keeps_metadata() -> TakResult<()> {
+    let game = PtnGame::<5>::from_ptn(PLAYTAK_GAME)?;
+    assert_eq!(game.tag("Player1"), Some("WilemBot"));
+    assert_eq!(game.tag("Clock"), Some("10:0 +10"));
+    assert_eq!(game.tags.len(), 12);
+    assert_eq!(game.comments, vec!["a friendly game"]);
+    assert_eq!(game.plies[3].annotation, "?!");
+    assert_eq!(game.plies[2].comments, vec!["center"]);
+    assert_eq!(game.plies[7].comments, vec!["threatening \"b\" file"]);
+    assert_eq!(game.plies[10].annotation, "*'");
+    assert_eq!(game.plies[12].annotation, "\"");
+    assert_eq!(game.plies[12].comments, vec!["road"]);
+    assert_eq!(game.result.as_deref(), Some("R-0"));
+
+    assert_eq!(PtnGame::from_ptn(&game.to_ptn())?, game);
+    assert_eq!(game.record()?.game().winner(), GameResult::Winner {
+        colour: Colour::White,
+        road: true
+    });
+    Ok(())
+}
+
+#[test]
+fn ptn_game_comments_stay_in_place() -> TakResult<()> {
+    let ptn = r#"{before the tags}
+[Size "5"]
+{between the tags}
+[Komi "2"]
+{before the moves}
+1. a1 e5 {a reply}
+2. c3
+R-0 {after the result}
+"#;
+    let game = PtnGame::<5>::from_ptn(ptn)?;
+    assert_eq!(game.tag_comments, vec![
+        (0, "before the tags".to_string()),
+        (1, "between the tags".to_string())
+    ]);
+    assert_eq!(game.comments, vec!["before the moves"]);
+    assert_eq!(game.plies[1].comments, vec!["a reply"]);
+    assert!(game.plies[2].comments.is_empty());
+    assert_eq!(game.result_comments, vec!["after the result"]);
+
+    assert_eq!(game.to_ptn(), ptn);
+    assert_eq!(PtnGame::from_ptn(&game.to_ptn())?, game);
+    Ok(())
+}
+
+#[test]
+fn ptn_game_errors() {
+    // the result ends the move list
+    assert!(matches!(
+        PtnGame::<5>::from_ptn(&format!("{PLAYTAK_GAME}8. a2")),
+        Err(Error::ParseError { .. })
+    ));
+    assert!(matches!(
+        PtnGame::<5>::from_ptn("1. a1 {unclosed e5"),
+        Err(Error::ParseError { position: 6, .. })
+    ));
+    assert!(matches!(
+        PtnGame::<5>::from_ptn("[Size 5]"),
+        Err(Error::ParseError { position: 6, .. })
+    ));
+    assert!(matches!(
+        PtnGame::<5>::from_ptn("1. a1 e5\n[Size \"5\"]"),
+        Err(Error::ParseError { position: 9, .. })
+    ));
+}
+
+#[test]
+fn ptn_game_tag_errors() -> TakResult<()> {
+    // problems with the tags are found in the whole document
+    let ptn = "[Size \"5\"]\n[Komi \"half\"]\n1. a1 e5";
+    assert!(matches!(
+        PtnGame::<5>::from_ptn(ptn)?.start(),
+        Err(Error::ParseError { input, position: 18, .. }) if input == ptn
+    ));
+
+    let ptn = "[Size \"5\"]\n[TPS \"x5/x5/x5/x5/x3,3,x 1 1\"]";
+    assert!(matches!(
+        PtnGame::<5>::from_ptn(ptn)?.record(),
+        Err(Error::ParseError { input, position: 32, .. }) if input == ptn
+    ));
+
+    // unless the tag was changed after reading it
+    let mut game = PtnGame::<5>::from_ptn(ptn)?;
+    game.set_tag("TPS", "x5/x5/x5/x5/x4,4 1 1");
+    assert!(matches!(
+        game.start(),
+        Err(Error::ParseError { position: 15, .. })
+    ));
+    Ok(())
+}
+
+#[test]
+fn ptn_game_tag_escapes() -> TakResult<()> {
+    let mut game = PtnGame::<5>::from_ptn(r#"[Event 'Say "hi" \\ \'bye\'']"#)?;
+    assert_eq!(game.tag("Event"), Some(r#"Say "hi" \ 'bye'"#));
+    game.set_tag("Player1", "alion02");
+    game.set_tag("Event", "Tak \"Cup\"");
+    assert_eq!(
+        game.to_ptn(),
+        "[Event \"Tak \\\"Cup\\\"\"]\n[Player1 \"alion02\"]\n"
+    );
+    assert_eq!(PtnGame::from_ptn(&game.to_ptn())?, game);
+    Ok(())
+}
+
+#[test]
+fn read_database() {
+    let database = format!(
+        "\n{PLAYTAK_GAME}\n[Size \"5\"]\n[Player1 \"corrupt\"]\n1. a1 e5\n2. f9\n\n{PLAYTAK_GAME}[Size \
+         \"6\"]\n\n1. a1 f6\n"
+    );
+    let games: Vec<_> = PtnReader::<_, 5>::new(database.as_bytes()).collect();
+    assert_eq!(games.len(), 4);
+    assert_eq!(games.iter().map(|g| g.line).collect::<Vec<_>>(), [2, 24, 29, 50]);
+    assert_eq!(games[0].game, PtnGame::from_ptn(PLAYTAK_GAME));
+    assert!(matches!(games[1].game, Err(Error::ParseError { .. })));
+    assert_eq!(games[2].game, games[0].game);
+    assert!(matches!(games[3].game, Err(Error::ParseError { .. })));
+}
+
+#[test]
+fn read_database_tags() {
+    let database = "[Event \"a]b \\\"c]\"]\n1. a1 e5\n[Event \"d\"]\n1. a1 e5 2. c3\n";
+    let games: Vec<_> = PtnReader::<_, 5>::new(database.as_bytes()).collect();
+    assert_eq!(games.len(), 2);
+    let first = games[0].game.as_ref().unwrap();
+    assert_eq!(first.tag("Event"), Some("a]b \"c]"));
+    assert_eq!(first.plies.len(), 2);
+    assert_eq!(games[1].game.as_ref().unwrap().plies.len(), 3);
+
+    // values can be quoted with either quote
+    let database = "[Event 'a]b \"c]']\n[Site \"d\"]\n1. a1 e5\n[Event 'e']\n1. a1\n";
+    let games: Vec<_> = PtnReader::<_, 5>::new(database.as_bytes()).collect();
+    assert_eq!(games.iter().map(|g| g.line).collect::<Vec<_>>(), [1, 4]);
+    let first = games[0].game.as_ref().unwrap();
+    assert_eq!(first.tag("Event"), Some("a]b \"c]"));
+    assert_eq!(first.tag("Site"), Some("d"));
+    assert_eq!(first.plies.len(), 2);
+
+    // a game without moves still ends at a blank line
+    let database = "[Event \"empty\"]\n[Size \"5\"]\n\n[Event \"full\"]\n1. a1 e5\n";
+    let games: Vec<_> = PtnReader::<_, 5>::new(database.as_bytes()).collect();
+    assert_eq!(games.len(), 2);
+    assert_eq!(games.iter().map(|g| g.line).collect::<Vec<_>>(), [1, 4]);
+    let empty = games[0].game.as_ref().unwrap();
+    assert_eq!(empty.tag("Event"), Some("empty"));
+    assert!(empty.plies.is_empty());
+    assert_eq!(games[1].game.as_ref().unwrap().tag("Event"), Some("full"));
+
+    // but blank lines between its tags do not end it
+    let database =
+        "[Size \"5\"]\n\n[TPS \"x5/x5/x5/x5/1,x4 2 1\"]\n\n1. -- e5\n[Size \"5\"]\n\n[Event \"next\"]\n";
+    let games: Vec<_> = PtnReader::<_, 5>::new(database.as_bytes()).collect();
+    assert_eq!(games.iter().map(|g| g.line).collect::<Vec<_>>(), [1, 6]);
+    let first = games[0].game.as_ref().unwrap();
+    assert_eq!(first.tag("TPS"), Some("x5/x5/x5/x5/1,x4 2 1"));
+    assert_eq!(first.plies.len(), 1);
+    assert_eq!(games[1].game.as_ref().unwrap().tag("Event"), Some("next"));
+}
+
+#[test]
+fn strict_turn_parsing() -> TakResult<()> {
+    for (ptn, position) in [
+        ("xxa1yy", 0),
+        ("a1yy", 2),
+        ("3a1>12extra", 6),
+        ("`1", 0),
+        ("f1", 0),
+        ("a0", 1),
+        ("a6", 1),
+        ("a", 1),
+        ("", 0),
+        ("3Sa1", 1),
+        ("Ca1>", 3),
+        ("3a1", 3),
+        ("0a1>", 0),
+        ("6a1>", 0),
+        ("3a1>13", 5),
+        ("3a1>11", 4),
+        ("3a1>102", 5),
+        ("a1>'x", 4),
+    ] {
+        match Turn::<5>::from_ptn(ptn) {
+            Err(Error::ParseError { position: p, .. }) => assert_eq!(p, position, "{ptn:?}"),
+            other => panic!("{ptn:?} gave {other:?}"),
+        }
+    }
+    assert!(matches!(
+        Pos::<5>::from_ptn("a1>"),
+        Err(Error::ParseError { position: 2, .. })
+    ));
+
+    assert_eq!(Turn::<5>::from_ptn("3a1>12*'")?, Turn::from_ptn("3a1>12")?);
+    assert_eq!(Turn::<5>::from_ptn("Sc3?!")?, Turn::from_ptn("Sc3")?);
+    let turn = Turn::<10>::from_ptn("10j10<55")?;
+    assert_eq!(turn.to_ptn(), "10j10<55");
+    Ok(())
+}
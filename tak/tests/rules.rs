@@ -0,0 +1,232 @@
+use tak::*;
+
+#[test]
+fn standard_rules() {
+    let rules = Rules::standard(6).unwrap();
+    assert_eq!((rules.stones, rules.caps), default_starting_stones(6));
+    assert_eq!(rules.turn_limit, Some(400));
+    assert!(rules.swap);
+    assert_eq!(Game::<6>::default().rules, rules);
+    assert_eq!(Rules::standard(9), None);
+}
+
+#[test]
+fn custom_reserves() -> TakResult<()> {
+    let mut game = Game::<3>::with_rules(Rules {
+        stones: 2,
+        caps: 1,
+        ..Rules::standard(3).unwrap()
+    })?;
+    assert_eq!(game.get_counts(), (2, 1));
+    game.play(Turn::from_ptn("a1")?)?;
+    game.play(Turn::from_ptn("c3")?)?;
+    game.play(Turn::from_ptn("Cb2")?)?;
+    assert!(game.possible_turns().contains(&Turn::from_ptn("Sa3")?));
+    game.play(Turn::from_ptn("a3")?)?;
+
+    // White runs out of pieces, and Black has more flats on the board
+    assert_eq!(game.get_counts(), (1, 0));
+    game.play(Turn::from_ptn("Sc1")?)?;
+    assert_eq!(game.winner(), GameResult::Winner {
+        colour: Colour::Black,
+        road: false
+    });
+    Ok(())
+}
+
+#[test]
+fn turn_limit() -> TakResult<()> {
+    let rules = Rules {
+        turn_limit: Some(4),
+        ..Rules::standard(5).unwrap()
+    };
+    let mut game = Game::<5>::with_rules(rules)?;
+    for ply in ["a1", "e5", "b1", "d5"] {
+        assert_eq!(game.winner(), GameResult::Ongoing);
+        game.play(Turn::from_ptn(ply)?)?;
+    }
+    assert_eq!(game.winner(), GameResult::Draw { turn_limit: true });
+
+    game.rules.turn_limit = None;
+    assert_eq!(game.winner(), GameResult::Ongoing);
+    Ok(())
+}
+
+#[test]
+fn swap_reserves() -> TakResult<()> {
+    // the first pieces are taken from the reserves of their own colour
+    let mut game = Game::<5>::default();
+    game.play(Turn::from_ptn("a1")?)?;
+    assert_eq!((game.white_stones, game.black_stones), (21, 20));
+    assert_eq!(game.get_counts(), (20, 1));
+    game.play(Turn::from_ptn("e5")?)?;
+    assert_eq!((game.white_stones, game.black_stones), (20, 20));
+    assert_eq!(game.board.piece_counts(Colour::White), (1, 0));
+
+    let mut game = Game::<5>::default();
+    let undo = game.play_undoable(Turn::from_ptn("a1")?)?;
+    game.undo(undo);
+    assert_eq!(game, Game::default());
+    Ok(())
+}
+
+#[test]
+fn no_swap() -> TakResult<()> {
+    let mut game = Game::<5>::with_rules(Rules {
+        swap: false,
+        ..Rules::standard(5).unwrap()
+    })?;
+    assert!(!game.swap());
+    assert_eq!(game.colour(), Colour::White);
+    assert_eq!(game.possible_turns().len(), 3 * 25);
+    game.play(Turn::from_ptn("Cc3")?)?;
+    game.play(Turn::from_ptn("Sc2")?)?;
+    assert_eq!(game.board.to_tps(), "x5/x5/x2,1C,x2/x2,2S,x2/x5");
+    Ok(())
+}
+
+#[test]
+fn rules_in_ptn() -> TakResult<()> {
+    let rules = Rules {
+        stones: 12,
+        caps: 1,
+        komi: Komi::from(1),
+        ..Rules::standard(4).unwrap()
+    };
+    let mut record = GameRecord::new(Game::<4>::with_rules(rules)?);
+    record.play(Turn::from_ptn("a1")?)?;
+    record.play(Turn::from_ptn("d4")?)?;
+    record.play(Turn::from_ptn("Cb2")?)?;
+
+    let ptn = record.to_ptn();
+    assert!(ptn.starts_with("[Size \"4\"]\n[Komi \"1\"]\n[Flats \"12\"]\n[Caps \"1\"]\n"));
+    let copy = GameRecord::<4>::from_ptn(&ptn)?;
+    assert_eq!(copy.game().rules, rules);
+    assert_eq!(copy, record);
+
+    // reserves in standard TPS are counted from the rules
+    let game =
+        PtnGame::<4>::from_ptn("[Flats \"12\"]\n[Caps \"1\"]\n[TPS \"x4/x4/x,1C,x2/2,x3 2 2\"]")?.start()?;
+    assert_eq!((game.white_stones, game.white_caps), (12, 0));
+    assert_eq!((game.black_stones, game.black_caps), (11, 1));
+    Ok(())
+}
+
+#[test]
+fn half_komi() -> TakResult<()> {
+    // placing on the last square fills the board with one flat more for White
+    for (komi, result) in [
+        ("0.5", GameResult::Winner {
+            colour: Colour::White,
+            road: false,
+        }),
+        ("1", GameResult::Draw { turn_limit: false }),
+        ("1.5", GameResult::Winner {
+            colour: Colour::Black,
+            road: false,
+        }),
+    ] {
+        let mut game = Game::<3>::from_tps(&format!("1,2,1/2,x,2/1,2,1 1 5 (6/0) (6/0) {komi}"))?;
+        assert_eq!(game.rules.komi.to_string(), komi);
+        assert_eq!(Game::from_tps(&game.to_tps())?, game);
+        game.play(Turn::from_ptn("b2")?)?;
+        assert_eq!(game.winner(), result);
+    }
+    Ok(())
+}
+
+#[test]
+fn komi_strings() -> TakResult<()> {
+    for (s, half_points) in [("2.5", 5), ("2", 4), ("0", 0), ("-0.5", -1), ("-3", -6)] {
+        let komi: Komi = s.parse()?;
+        assert_eq!(komi.half_points(), half_points);
+        assert_eq!(komi.to_string(), s);
+    }
+    assert_eq!("2.0".parse::<Komi>()?, Komi::from(2));
+    for s in ["2.25", "half", "", "NaN", "inf"] {
+        assert!(s.parse::<Komi>().is_err(), "{s}");
+    }
+
+    let mut record = GameRecord::new(Game::<6>::with_komi(Komi::from_half_points(5)));
+    record.play(Turn::from_ptn("a1")?)?;
+    let ptn = record.to_ptn();
+    assert!(ptn.contains("[Komi \"2.5\"]\n"));
+    assert_eq!(GameRecord::from_ptn(&ptn)?, record);
+    Ok(())
+}
+
+#[test]
+fn swap_and_turn_limit_round_trip() -> TakResult<()> {
+    let rules = Rules {
+        turn_limit: None,
+        swap: false,
+        ..Rules::standard(5).unwrap()
+    };
+    let mut record = GameRecord::new(Game::<5>::with_rules(rules)?);
+    record.play(Turn::from_ptn("Sa1")?)?;
+    record.play(Turn::from_ptn("Cc3")?)?;
+
+    let ptn = record.to_ptn();
+    assert!(ptn.contains("[Opening \"no-swap\"]\n[TurnLimit \"none\"]\n"));
+    let copy = GameRecord::<5>::from_ptn(&ptn)?;
+    assert_eq!(copy.game().rules, rules);
+    assert_eq!(copy, record);
+
+    // only the rules which are not standard get a tag
+    let short = GameRecord::new(Game::<5>::with_rules(Rules {
+        turn_limit: Some(100),
+        ..Rules::standard(5).unwrap()
+    })?);
+    let ptn = short.to_ptn();
+    assert!(ptn.contains("[TurnLimit \"100\"]") && !ptn.contains("[Opening "));
+    assert_eq!(GameRecord::<5>::from_ptn(&ptn)?, short);
+
+    let game = record.game();
+    assert!(game.to_tps().ends_with(" 0 none no-swap"));
+    assert_eq!(Game::<5>::from_tps(&game.to_tps())?, *game);
+
+    let game = Game::<5>::from_tps("x5/x5/x5/x5/x5 1 1 (21/1) (21/1) 0 30 swap")?;
+    assert_eq!((game.rules.turn_limit, game.rules.swap), (Some(30), true));
+    for tps in [
+        "x5/x5/x5/x5/x5 1 1 (21/1) (21/1) 0 30",
+        "x5/x5/x5/x5/x5 1 1 (21/1) (21/1) 0 -1 swap",
+        "x5/x5/x5/x5/x5 1 1 (21/1) (21/1) 0 30 maybe",
+    ] {
+        assert!(
+            matches!(Game::<5>::from_tps(tps), Err(Error::ParseError { .. })),
+            "{tps}"
+        );
+    }
+    assert!(PtnGame::<5>::from_ptn("[Opening \"swapped\"]")?.start().is_err());
+    assert!(PtnGame::<5>::from_ptn("[TurnLimit \"never\"]")?.start().is_err());
+    Ok(())
+}
+
+#[test]
+fn invalid_rules() -> TakResult<()> {
+    let rules = |stones, caps| Rules {
+        stones,
+        caps,
+        ..Rules::standard(8).unwrap()
+    };
+    // every piece fits in one stack
+    assert!(Game::<8>::with_rules(rules(62, 2)).is_ok());
+    for (stones, caps) in [(63, 2), (200, 0), (0, 1)] {
+        let err = Error::InvalidRules { stones, caps };
+        assert_eq!(rules(stones, caps).check(), Err(err.clone()));
+        assert_eq!(Game::<8>::with_rules(rules(stones, caps)), Err(err));
+        assert_eq!(
+            GameBuilder::<8>::with_rules(rules(stones, caps)).build(),
+            Err(SetupError::InvalidRules)
+        );
+    }
+    assert!(matches!(
+        PtnGame::<5>::from_ptn("[Flats \"100\"]")?.record(),
+        Err(Error::InvalidRules { .. })
+    ));
+    assert!(matches!(
+        PtnGame::<5>::from_ptn("[Flats \"100\"]\n[TPS \"x5/x5/x5/x5/x5 1 1\"]")?.start(),
+        Err(Error::InvalidRules { .. })
+    ));
+    Ok(())
+}
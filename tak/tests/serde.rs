@@ -0,0 +1,68 @@
+#![cfg(feature = "serde")]
+
+use serde::{de::DeserializeOwned, Serialize};
+use tak::*;
+
+fn round_trip<T: Serialize + DeserializeOwned + PartialEq + std::fmt::Debug>(value: T, json: &str) {
+    assert_eq!(serde_json::to_string(&value).unwrap(), json);
+    assert_eq!(serde_json::from_str::<T>(json).unwrap(), value);
+}
+
+#[test]
+fn json_forms() -> TakResult<()> {
+    round_trip(Pos::<5>::from_ptn("c4")?, r#""c4""#);
+    round_trip(Turn::<5>::from_ptn("3c3>12")?, r#""3c3>12""#);
+    round_trip(Turn::<5>::from_ptn("Sa1")?, r#""Sa1""#);
+    round_trip(Tile::from_tps("212C")?, r#""212C""#);
+    round_trip(
+        Piece {
+            colour: Colour::Black,
+            shape: Shape::Wall,
+        },
+        r#""2S""#,
+    );
+    round_trip(Colour::White, r#""white""#);
+    round_trip(Komi::from_half_points(5), r#""2.5""#);
+    round_trip(GameResult::Ongoing, r#""ongoing""#);
+    round_trip(
+        GameResult::Winner {
+            colour: Colour::Black,
+            road: true,
+        },
+        r#"{"winner":{"colour":"black","road":true}}"#,
+    );
+
+    let game = Game::<5>::from_ptn("1. a1 e5 2. Cc3 d4 3. c3> Sb2")?;
+    round_trip(game.board.clone(), r#""x4,1/x3,2,x/x3,1C,x/x,2S,x3/2,x4""#);
+    round_trip(
+        game,
+        concat!(
+            r#"{"tps":"x4,1/x3,2,x/x3,1C,x/x,2S,x3/2,x4 1 4 (20/0) (18/1) 0 400 swap","#,
+            r#""rules":{"stones":21,"caps":1,"turn_limit":400,"swap":true,"komi":"0"}}"#
+        ),
+    );
+
+    // the rules which TPS cannot hold survive the round trip
+    let mut game = Game::<5>::with_rules(Rules {
+        stones: 15,
+        caps: 2,
+        turn_limit: None,
+        swap: false,
+        komi: Komi::from_half_points(3),
+    })?;
+    game.play_ptn_moves(&["Sa1", "Cb2"])?;
+    let json = serde_json::to_string(&game).unwrap();
+    assert_eq!(serde_json::from_str::<Game<5>>(&json).unwrap(), game);
+    Ok(())
+}
+
+#[test]
+fn json_errors() {
+    assert!(serde_json::from_str::<Turn<5>>(r#""f1""#).is_err());
+    assert!(serde_json::from_str::<Board<5>>(r#""x5/x5""#).is_err());
+    assert!(serde_json::from_str::<Game<5>>(r#""x5/x5/x5/x5/x5 1 1""#).is_err());
+    assert!(serde_json::from_str::<Tile>(r#""12x""#).is_err());
+    assert!(serde_json::from_str::<Tile>(&format!("\"{}\"", "1".repeat(300))).is_err());
+    assert!(serde_json::from_str::<Piece>(r#""12""#).is_err());
+    assert!(serde_json::from_str::<Pos<5>>("3").is_err());
+}
@@ -0,0 +1,102 @@
+mod common;
+
+use common::check_line;
+use tak::*;
+
+#[test]
+fn road_in_one() -> TakResult<()> {
+    let game = Game::<5>::from_tps("x5/x5/2,2,2,2,x/1,1,1S,x2/x2,1,1,x 2 8")?;
+    let mut solver = Solver::new();
+    assert_eq!(solver.solve(&game), Solution::Win);
+    assert_eq!(solver.get(&game), Some(Solution::Win));
+    assert_eq!(solver.principal_line(&game), vec![Turn::from_ptn("e3")?]);
+    Ok(())
+}
+
+#[test]
+fn proves_tinue() -> TakResult<()> {
+    let game = Game::<5>::from_tps("2,2,2,x2/2,2,2,x,1/x4,1/x4,1/x,1,1,1,x 1 7")?;
+    let mut solver = Solver::new();
+    assert!(solver.proves(&game, Colour::White));
+    check_line(&game, &solver.principal_line(&game), Colour::White)?;
+    Ok(())
+}
+
+#[test]
+fn last_placement() -> TakResult<()> {
+    // placing on the last square fills the board and wins on flats
+    let game = Game::<3>::from_tps("1,2,1/2,x,2/1,2,1 1 5 (6/0) (6/0) 0")?;
+    let mut solver = Solver::new();
+    assert_eq!(solver.solve(&game), Solution::Win);
+    check_line(&game, &solver.principal_line(&game), Colour::White)?;
+
+    // unless komi makes it a draw
+    let game = Game::<3>::from_tps("1,2,1/2,x,2/1,2,1 1 5 (6/0) (6/0) 1")?;
+    assert_eq!(solver.solve(&game), Solution::Draw);
+    Ok(())
+}
+
+#[test]
+fn turn_limit() -> TakResult<()> {
+    // the same tinue runs out of plies when it starts too late
+    let tps =
+        |move_num| format!("2,2,2,x2/2,2,2,x,1/x4,1/x4,1/x,1,1,1,x 1 {move_num} (15/1) (15/1) 0 14 swap");
+    let late = Game::<5>::from_tps(&tps(7))?;
+    let early = Game::<5>::from_tps(&tps(6))?;
+    let mut solver = Solver::new();
+    assert!(!solver.proves(&late, Colour::White));
+    assert!(solver.proves(&early, Colour::White));
+    check_line(&early, &solver.principal_line(&early), Colour::White)?;
+    assert!(!solver.proves(&late, Colour::White));
+    Ok(())
+}
+
+#[test]
+fn transpositions_across_plies() -> TakResult<()> {
+    let tps = |move_num, turn_limit| {
+        format!("x5/x5/2,2,2,2,x/1,1,1S,x2/x2,1,1,x 2 {move_num} (16/1) (17/1) 0 {turn_limit} swap")
+    };
+    let game = Game::<5>::from_tps(&tps(8, "none"))?;
+    let later = Game::<5>::from_tps(&tps(12, "none"))?;
+    let mut solver = Solver::new();
+    assert_eq!(solver.solve(&game), Solution::Win);
+    assert_eq!(solver.get(&later), Some(Solution::Win));
+    let len = solver.len();
+    assert_eq!(solver.solve(&later), Solution::Win);
+    assert_eq!(solver.len(), len);
+
+    // with a turn limit the ply is part of the position
+    let game = Game::<5>::from_tps(&tps(8, "400"))?;
+    let later = Game::<5>::from_tps(&tps(12, "400"))?;
+    let mut solver = Solver::new();
+    assert_eq!(solver.solve(&game), Solution::Win);
+    assert_eq!(solver.get(&later), None);
+    Ok(())
+}
+
+#[test]
+fn solved_positions_agree() -> TakResult<()> {
+    let mut solver = Solver::new();
+    let mut game = Game::<3>::default();
+    for n in [3, 7, 2, 5, 4, 1] {
+        game.nth_move(n)?;
+    }
+
+    let solution = solver.solve(&game);
+    // wins and losses are backed up by every reply in the table
+    let replies: Vec<_> = game
+        .possible_turns()
+        .into_iter()
+        .map(|turn| {
+            let mut next = game.clone();
+            next.play(turn).unwrap();
+            solver.get(&next)
+        })
+        .collect();
+    match solution {
+        Solution::Win => assert!(replies.contains(&Some(Solution::Loss))),
+        Solution::Loss => assert!(replies.iter().all(|&reply| reply == Some(Solution::Win))),
+        Solution::Draw => assert!(!replies.contains(&Some(Solution::Loss))),
+    }
+    Ok(())
+}
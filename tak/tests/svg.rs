@@ -0,0 +1,51 @@
+use tak::*;
+
+#[test]
+fn svg_position() -> TakResult<()> {
+    let game = Game::<5>::from_ptn("1. a1 e5 2. b1 b2 3. Cc1 c2 4. c1< Sd2")?;
+    let svg = game.to_svg(&SvgOptions::default());
+    assert!(svg.starts_with("<svg "));
+    assert!(svg.ends_with("</svg>\n"));
+    assert_eq!(svg.matches("class=\"square\"").count(), 25);
+    assert_eq!(svg.matches("class=\"flat\"").count(), 4);
+    assert_eq!(svg.matches("class=\"wall\"").count(), 1);
+    assert_eq!(svg.matches("class=\"capstone\"").count(), 1);
+    // the white flat under the capstone on b1
+    assert_eq!(svg.matches("class=\"buried\"").count(), 1);
+    assert!(svg.contains("White: 19 stones, 0 capstones (to move)"));
+    assert!(svg.contains("Black: 17 stones, 1 capstone<"));
+    assert!(!svg.contains("class=\"heat\""));
+    assert!(!svg.contains("class=\"road\""));
+    assert!(!svg.contains("class=\"last-turn\""));
+    Ok(())
+}
+
+#[test]
+fn svg_overlays() -> TakResult<()> {
+    let game = Game::<5>::from_ptn("1. d2 a5 2. b4 d3 3. Cc3 Cc2 4. b2 b1 5. b3 a1 6. c4 c1 7. e2 e3")?;
+    let road = match game.outcome().reason {
+        Some(EndReason::Road { squares }) => squares,
+        reason => panic!("expected a road, got {reason:?}"),
+    };
+    let mut heatmap = [[0.; 5]; 5];
+    heatmap[0][0] = 0.5;
+    heatmap[4][4] = 2.;
+    let svg = game.to_svg(&SvgOptions {
+        last_turn: Some(Turn::from_ptn("e3")?),
+        heatmap: Some(heatmap),
+        road: Some(road),
+    });
+    assert_eq!(svg.matches("class=\"road\"").count(), road.count() as usize);
+    assert_eq!(svg.matches("class=\"heat\"").count(), 2);
+    assert!(svg.contains("fill-opacity=\"0.350\""));
+    assert!(svg.contains("fill-opacity=\"0.700\""));
+    assert_eq!(svg.matches("<rect class=\"last-turn\"").count(), 1);
+
+    let svg = Game::<5>::default().to_svg(&SvgOptions {
+        last_turn: Some(Turn::from_ptn("2a2>11")?),
+        ..Default::default()
+    });
+    // from the centre of a2 to the centre of c2
+    assert!(svg.contains("<line class=\"last-turn\" x1=\"56\" y1=\"232\" x2=\"184\" y2=\"232\""));
+    Ok(())
+}
@@ -51,7 +51,7 @@ fn mirror_odd() {
 }
 
 #[test]
-fn symmetrical_boards() -> StrResult<()> {
+fn symmetrical_boards() -> TakResult<()> {
     let [mut g0, mut g1, mut g2, mut g3, mut g4, mut g5, mut g6, mut g7] = Game::<5>::default().symmetries();
     while matches!(g0.winner(), GameResult::Ongoing) {
         let turns = g0.possible_turns();
@@ -75,3 +75,40 @@ fn symmetrical_boards() -> StrResult<()> {
     assert_eq!(g6.winner(), g7.winner());
     Ok(())
 }
+
+#[test]
+fn inverse_symmetries() -> TakResult<()> {
+    for turn in ["b1", "Cd2", "3c2>12", "2a4-11"] {
+        let turn = Turn::<5>::from_ptn(turn)?;
+        for (i, symm) in turn.clone().symmetries().into_iter().enumerate() {
+            assert_eq!(turn.clone().symmetry(i), symm);
+            assert_eq!(symm.inverse_symmetry(i), turn);
+        }
+    }
+    Ok(())
+}
+
+#[test]
+fn canonical_position() -> TakResult<()> {
+    let game = Game::<5>::from_ptn("1. a1 b4 2. Cc3 d4 3. c3< d3 4. b3> a2")?;
+    let (canonical, index) = game.canonical();
+    assert_eq!(canonical, game.clone().symmetry(index));
+    assert_eq!(canonical.hash(), canonical.compute_hash());
+    for symm in game.clone().symmetries() {
+        assert_eq!(symm.canonical().0, canonical);
+    }
+
+    // a piece in any corner ends up on the last square
+    let (corner, _) = Game::<5>::from_ptn("1. a5")?.canonical();
+    assert!(corner.board[Pos::from_ptn("e5")?].is_some());
+
+    // turns in the canonical position map back to the same position
+    for turn in canonical.possible_turns() {
+        let mut after = canonical.clone();
+        after.play(turn.clone())?;
+        let mut original = game.clone();
+        original.play(turn.inverse_symmetry(index))?;
+        assert_eq!(original.canonical().0, after.canonical().0);
+    }
+    Ok(())
+}
@@ -0,0 +1,129 @@
+mod common;
+
+use common::check_line;
+use tak::*;
+
+#[test]
+fn road_in_one() -> TakResult<()> {
+    let game = Game::<5>::from_tps("x5/x5/2,2,2,2,x/1,1,1S,x2/x2,1,1,x 2 8")?;
+    let line = game.tinue(Colour::Black, 3).unwrap();
+    assert_eq!(line, vec![Turn::from_ptn("e3")?]);
+    assert_eq!(game.tinue(Colour::White, 3), None);
+    Ok(())
+}
+
+#[test]
+fn double_threat() -> TakResult<()> {
+    let game = Game::<5>::from_tps("2,2,2,x2/2,2,2,x,1/x4,1/x4,1/x,1,1,1,x 1 7")?;
+    assert!(game.winning_moves(Colour::White).is_empty());
+    assert_eq!(game.tinue(Colour::White, 1), None);
+
+    let line = game.tinue(Colour::White, 2).unwrap();
+    assert_eq!(line.len(), 3);
+    assert_eq!(game.tinue_with_budget(Colour::White, 2, 1), None);
+    assert_eq!(
+        game.tinue_with_budget(Colour::White, 2, 10_000),
+        Some(line.clone())
+    );
+    assert!(matches!(
+        check_line(&game, &line, Colour::White)?,
+        GameResult::Winner { road: true, .. }
+    ));
+
+    // after the first move every defence loses
+    let mut after = game.clone();
+    after.play(line[0].clone())?;
+    let defended = after.tinue(Colour::White, 1).unwrap();
+    assert!(matches!(
+        check_line(&after, &defended, Colour::White)?,
+        GameResult::Winner { road: true, .. }
+    ));
+    Ok(())
+}
+
+/// Whether the attacker, who is to move, wins within `depth` of its turns
+/// against every defence. Unlike [`Game::tinue`] this tries every turn.
+fn forced_win<const N: usize>(game: &Game<N>, attacker: Colour, depth: usize) -> TakResult<bool> {
+    if depth == 0 {
+        return Ok(false);
+    }
+    if !game.winning_moves(attacker).is_empty() {
+        return Ok(true);
+    }
+    for turn in game.possible_turns() {
+        let mut after = game.clone();
+        after.play(turn)?;
+        if after.winner() == GameResult::Ongoing && every_reply_loses(&after, attacker, depth - 1)? {
+            return Ok(true);
+        }
+    }
+    Ok(false)
+}
+
+/// Whether every reply of the defender, who is to move, leaves the
+/// attacker a forced win within `depth` of its turns.
+fn every_reply_loses<const N: usize>(game: &Game<N>, attacker: Colour, depth: usize) -> TakResult<bool> {
+    for turn in game.possible_turns() {
+        let mut after = game.clone();
+        after.play(turn)?;
+        let lost = match after.winner() {
+            GameResult::Winner { colour, .. } => colour == attacker,
+            GameResult::Ongoing => forced_win(&after, attacker, depth)?,
+            GameResult::Draw { .. } => false,
+        };
+        if !lost {
+            return Ok(false);
+        }
+    }
+    Ok(true)
+}
+
+/// Whether the line wins for the attacker within `depth` of its turns, and
+/// the defender could not have escaped with any other reply along it.
+fn is_forced<const N: usize>(
+    game: &Game<N>,
+    line: &[Turn<N>],
+    attacker: Colour,
+    mut depth: usize,
+) -> TakResult<bool> {
+    let mut game = game.clone();
+    for turn in line {
+        if game.to_move == attacker {
+            depth -= 1;
+        } else if !every_reply_loses(&game, attacker, depth)? {
+            return Ok(false);
+        }
+        game.play(turn.clone())?;
+    }
+    Ok(matches!(game.winner(), GameResult::Winner { colour, road: true } if colour == attacker))
+}
+
+#[test]
+fn tinue_lines_are_forced() -> TakResult<()> {
+    let mut found = 0;
+    for seed in [1_000_000_007, 998_244_353, 9_576_890_767] {
+        let mut game = Game::<4>::default();
+        while game.winner() == GameResult::Ongoing {
+            let colour = game.to_move;
+            if let Some(line) = game.tinue(colour, 2) {
+                check_line(&game, &line, colour)?;
+                assert!(is_forced(&game, &line, colour, 2)?, "{}", game.to_tps());
+                found += 1;
+            }
+            game.nth_move(seed)?;
+        }
+    }
+    assert!(found > 0);
+    Ok(())
+}
+
+#[test]
+fn escapable_lines_are_not_forced() -> TakResult<()> {
+    // Black can block the road instead
+    let game = Game::<5>::from_tps("x5/x5/2,2,x3/x5/1,1,1,1,x 2 5")?;
+    let line = [Turn::from_ptn("a5")?, Turn::from_ptn("e1")?];
+    check_line(&game, &line, Colour::White)?;
+    assert!(!is_forced(&game, &line, Colour::White, 1)?);
+    assert_eq!(game.tinue(Colour::White, 1), None);
+    Ok(())
+}
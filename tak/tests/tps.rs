@@ -33,7 +33,7 @@ fn with_stacks_tps() {
 }
 
 #[test]
-fn tps_consistency() -> StrResult<()> {
+fn tps_consistency() -> TakResult<()> {
     let mut game = Game::<5>::default();
     for _ in 0..100 {
         game.nth_move(9576890767)?; // some 10 digit prime to seed pseudo-random moves
@@ -49,3 +49,97 @@ fn tps_consistency() -> StrResult<()> {
     }
     Ok(())
 }
+
+#[test]
+fn game_tps_round_trip() -> TakResult<()> {
+    let mut game = Game::<6>::with_komi(2);
+    for _ in 0..60 {
+        game.nth_move(2_147_483_647)?;
+        let copy = Game::from_tps(&game.to_tps())?;
+        assert_eq!(game, copy);
+    }
+    Ok(())
+}
+
+#[test]
+fn game_from_standard_tps() -> TakResult<()> {
+    let game = Game::<5>::from_tps("x5/x5/x5/x5/2121C,x3,122S 2 8")?;
+    assert_eq!(game.to_move, Colour::Black);
+    assert_eq!(game.ply, 15);
+    assert_eq!((game.white_stones, game.white_caps), (19, 0));
+    assert_eq!((game.black_stones, game.black_caps), (17, 1));
+    assert_eq!(game.rules.komi, Komi::default());
+    Ok(())
+}
+
+#[test]
+fn malformed_game_tps() {
+    for tps in [
+        "x5/x5/x5/x5/x5",
+        "x5/x5/x5/x5/x5 3 1",
+        "x5/x5/x5/x5/x5 1 0",
+        "x5/x5/x5/x5/x5 2 18446744073709551615",
+        "x5/x5/x5/x5/x5 1 9223372036854775809",
+        "x5/x5/x5/x5/x4,1Cx 1 1",
+        "x5/x5/x5/x5/x4y,1 1 1",
+        "x5/x5/x5/x5/1,x3,12S3 1 1",
+        "x5/x5/x5/x5/x5 1 1 (21/1) (21/1)",
+        "x5/x5/x5/x5/x5 1 1 21/1 (21/1) 0",
+        "x5/x5/x5/x5/x5 1 1 (21/1) (21/1) half",
+        "x5/x5/x5/x4,1C/1C,x4 1 1",
+        "x5/x5/x5/x5/x5 1 1 (255/255) (255/255) 0",
+        "x5/x5/x5/x5/1,x4 2 1 (21/1) (21/1) 0",
+        "x5/x5/x5/x5/1C,x4 2 1 (21/1) (21/1) 0",
+    ] {
+        assert!(
+            matches!(Game::<5>::from_tps(tps), Err(Error::ParseError { .. })),
+            "{tps}"
+        );
+    }
+    assert!(matches!(
+        Game::<5>::from_tps("x5/x5/x5/x5/x3,3,x 1 1"),
+        Err(Error::ParseError { position: 15, .. })
+    ));
+    assert!(matches!(
+        Game::<5>::from_tps("x5/x5/x5/x5/2,x4 2 1 (21/1) (20/0) 0"),
+        Err(Error::ParseError { position: 28, .. })
+    ));
+}
+
+#[test]
+fn empty_tile_runs() -> TakResult<()> {
+    assert_eq!(
+        Board::<8>::from_tps("x8/x8/x8/x8/x8/x8/x8/x,x1,x6")?,
+        Board::default()
+    );
+    // runs start at one
+    for tps in ["x0,x5", "x01,x4", "x,x0,x4", "x-1,x6"] {
+        let rows = format!("x5/x5/x5/x5/{tps}");
+        assert!(
+            matches!(Board::<5>::from_tps(&rows), Err(Error::ParseError { .. })),
+            "{tps}"
+        );
+    }
+    // runs of more than one digit can still be longer than the row
+    for tps in ["x10", "x99999999999999999999"] {
+        let rows = format!("x5/x5/x5/x5/{tps}");
+        assert!(
+            matches!(Board::<5>::from_tps(&rows), Err(Error::ParseError { .. })),
+            "{tps}"
+        );
+    }
+    Ok(())
+}
+
+#[test]
+fn too_tall_stacks() {
+    let tall = "1".repeat(Stack::CAPACITY + 2);
+    assert!(matches!(Tile::from_tps(&tall), Err(Error::ParseError { .. })));
+    assert_eq!(
+        Tile::from_tps(&tall[1..]).map(|tile| tile.size()),
+        Ok(Stack::CAPACITY + 1)
+    );
+
+    let tps = format!("x6/x6/x6/x6/x6/{},x5 1 2", "12".repeat(100));
+    assert!(matches!(Game::<6>::from_tps(&tps), Err(Error::ParseError { .. })));
+}
@@ -0,0 +1,59 @@
+use tak::*;
+
+/// Play every possible turn and take it back, then continue with a
+/// pseudo-random turn.
+fn check_random_game<const N: usize>(seed: usize) -> TakResult<()> {
+    let mut game = Game::<N>::default();
+    while game.winner() == GameResult::Ongoing {
+        for turn in game.possible_turns() {
+            let copy = game.clone();
+            let mut played = game.clone();
+            played.play(turn.clone())?;
+
+            let undo = game.play_undoable(turn)?;
+            assert_eq!(game, played);
+            game.undo(undo);
+            assert_eq!(game, copy);
+        }
+        game.nth_move(seed)?;
+    }
+    Ok(())
+}
+
+#[test]
+fn undo_random_games() -> TakResult<()> {
+    check_random_game::<3>(1_000_000_007)?;
+    check_random_game::<4>(998_244_353)?;
+    check_random_game::<5>(9_576_890_767)?;
+    check_random_game::<6>(2_147_483_647)?;
+    Ok(())
+}
+
+#[test]
+fn undo_flattening() -> TakResult<()> {
+    let mut game = Game::<5>::from_ptn("1. a1 e1 2. Cc3 Sd3")?;
+    let copy = game.clone();
+    let undo = game.play_undoable(Turn::from_ptn("c3>")?)?;
+    assert_eq!(game.board.to_tps(), "x5/x5/x3,21C,x/x5/2,x3,1");
+    game.undo(undo);
+    assert_eq!(game, copy);
+    assert_eq!(game.board.to_tps(), "x5/x5/x2,1C,2S,x/x5/2,x3,1");
+    Ok(())
+}
+
+#[test]
+fn failed_turns_leave_the_game_unchanged() -> TakResult<()> {
+    // the first piece of each spread can be dropped, but not the second
+    let game = Game::<5>::from_tps("x5/x5/x5/x5/x,21,x,2S,x 1 5")?;
+    for (ptn, err) in [
+        ("2b1>11", Error::CannotFlattenWall),
+        ("2b1<11", Error::MoveOffBoard {
+            square: "b1".to_string(),
+        }),
+    ] {
+        let mut copy = game.clone();
+        assert_eq!(copy.play_undoable(Turn::from_ptn(ptn)?).err(), Some(err), "{ptn}");
+        assert_eq!(copy, game, "{ptn}");
+    }
+    Ok(())
+}
@@ -1,7 +1,7 @@
 use tak::*;
 
 #[test]
-fn double_road_correct_win() -> StrResult<()> {
+fn double_road_correct_win() -> TakResult<()> {
     let game = Game::<6>::from_ptn(
         "1. a4 a3
         2. b3 b4
@@ -20,7 +20,7 @@ fn double_road_correct_win() -> StrResult<()> {
 }
 
 #[test]
-fn flat_win() -> StrResult<()> {
+fn flat_win() -> TakResult<()> {
     let game = Game::<3>::from_ptn(
         "1. a3 c1
         2. c2 c3
@@ -36,7 +36,7 @@ fn flat_win() -> StrResult<()> {
 }
 
 #[test]
-fn road_win() -> StrResult<()> {
+fn road_win() -> TakResult<()> {
     let game = Game::<5>::from_ptn(
         "1. d2 a5
         2. b4 d3
@@ -54,7 +54,7 @@ fn road_win() -> StrResult<()> {
 }
 
 #[test]
-fn road_beats_flats() -> StrResult<()> {
+fn road_beats_flats() -> TakResult<()> {
     let mut game = Game::<5>::from_ptn(
         "[TPS \"2,1,1,1,2/x,1,11211112C,221C,21/1212,x,2,x2/x,1112S,x,2,1/221S,2121,x,2,x 1 42\"]
         [Komi \"2\"]",
@@ -66,3 +66,184 @@ fn road_beats_flats() -> StrResult<()> {
     });
     Ok(())
 }
+
+#[test]
+fn road_squares() -> TakResult<()> {
+    // winding road with a dead end at a4 and a wall at c1
+    let board = Board::<5>::from_tps("x,2,x3/2,2,2,x2/x2,2,x,1/1,x,2,2,x/x2,2S,2,x")?;
+    assert!(board.find_paths(Colour::Black));
+    assert!(!board.find_paths(Colour::White));
+    assert_eq!(board.road(Colour::White), None);
+
+    let road = board.road(Colour::Black).unwrap();
+    let squares: Vec<_> = road.positions().map(|pos| pos.to_ptn()).collect();
+    assert_eq!(squares, ["d1", "c2", "d2", "c3", "b4", "c4", "b5"]);
+    Ok(())
+}
+
+#[test]
+fn road_matches_winner() -> TakResult<()> {
+    for seed in [1_000_000_007, 998_244_353, 2_147_483_647] {
+        let mut game = Game::<6>::default();
+        while game.winner() == GameResult::Ongoing {
+            game.nth_move(seed)?;
+        }
+        for colour in [Colour::White, Colour::Black] {
+            let road = game.board.road(colour);
+            assert_eq!(road.is_some(), game.board.find_paths(colour));
+            if let Some(road) = road {
+                let pieces = game.board.road_pieces(colour);
+                assert_eq!(road & pieces, road);
+                // a road has no branches, so it is connected from any square
+                let start = road.positions().next().unwrap();
+                assert_eq!(Bitboard::default().with(start).flood_fill(road), road);
+            }
+        }
+    }
+    Ok(())
+}
+
+#[test]
+fn road_threats() -> TakResult<()> {
+    let game = Game::<5>::from_tps("x5/x5/2,2,2,2,x/1,1,1S,x2/x2,1,1,x 1 8")?;
+    let threats: Vec<_> = game
+        .road_threats(Colour::Black)
+        .positions()
+        .map(|p| p.to_ptn())
+        .collect();
+    assert_eq!(threats, ["e3"]);
+    assert!(game.road_threats(Colour::White).is_empty());
+
+    let mut winning: Vec<_> = game
+        .winning_moves(Colour::Black)
+        .iter()
+        .map(Turn::to_ptn)
+        .collect();
+    winning.sort();
+    assert_eq!(winning, ["Ce3", "e3"]);
+    // white can only stop it by spreading
+    assert!(game.winning_moves(Colour::White).is_empty());
+    Ok(())
+}
+
+#[test]
+fn winning_spreads() -> TakResult<()> {
+    // the capstone can flatten the wall at c3 to finish the road
+    let game = Game::<5>::from_tps("x5/x5/1,1,1S,1,1/x,x,21C,x2/2,2,x3 1 9")?;
+    assert!(game.road_threats(Colour::Black).is_empty());
+    assert_eq!(game.winning_moves(Colour::White), vec![Turn::from_ptn("c2+")?]);
+    Ok(())
+}
+
+#[test]
+fn winning_moves_match_winner() -> TakResult<()> {
+    fn check<const N: usize>(seed: usize) -> TakResult<()> {
+        let mut game = Game::<N>::default();
+        while game.winner() == GameResult::Ongoing {
+            let colour = game.to_move;
+            let (mut roads, mut flats) = (Vec::new(), Vec::new());
+            for turn in game.possible_turns() {
+                let mut copy = game.clone();
+                copy.play(turn.clone())?;
+                match copy.winner() {
+                    GameResult::Winner {
+                        colour: c,
+                        road: true,
+                    } if c == colour => roads.push(turn),
+                    GameResult::Winner {
+                        colour: c,
+                        road: false,
+                    } if c == colour => flats.push(turn),
+                    _ => {}
+                }
+            }
+            for (mut winning, mut expected) in [
+                (game.winning_moves(colour), roads),
+                (game.flat_winning_moves(colour), flats),
+            ] {
+                winning.sort_by_key(Turn::to_ptn);
+                expected.sort_by_key(Turn::to_ptn);
+                assert_eq!(winning, expected);
+            }
+            game.nth_move(seed)?;
+        }
+        Ok(())
+    }
+    check::<5>(2_147_483_647)?;
+    for seed in [1_000_000_007, 998_244_353, 7_919_000_111] {
+        check::<3>(seed)?;
+        check::<4>(seed)?;
+    }
+    Ok(())
+}
+
+#[test]
+fn flat_winning_moves() -> TakResult<()> {
+    // filling the last square wins on flats, but no road is possible
+    let game = Game::<3>::from_tps("2S,2S,x/2,1,2/2,1,1 1 5")?;
+    assert!(game.winning_moves(Colour::White).is_empty());
+    assert_eq!(game.flat_winning_moves(Colour::White), vec![Turn::from_ptn(
+        "c3"
+    )?]);
+
+    // placing the last piece ends the game
+    let mut game = Game::<5>::with_rules(Rules {
+        stones: 3,
+        caps: 0,
+        ..Rules::standard(5).unwrap()
+    })?;
+    game.play_ptn_moves(&["a1", "e5", "c3", "b2"])?;
+    // a flat on any empty square, but not a wall
+    let winning: Vec<_> = game
+        .flat_winning_moves(Colour::White)
+        .iter()
+        .map(Turn::to_ptn)
+        .collect();
+    assert_eq!(winning.len(), 21);
+    assert!(!winning.iter().any(|ptn| ptn.starts_with('S')));
+    assert!(Game::<5>::default().flat_winning_moves(Colour::White).is_empty());
+    Ok(())
+}
+
+#[test]
+fn outcome_reasons() -> TakResult<()> {
+    let game = Game::<5>::from_ptn("1. d2 a5 2. b4 d3 3. Cc3 Cc2 4. b2 b1 5. b3 a1 6. c4 c1 7. e2 e3")?;
+    let outcome = game.outcome();
+    assert_eq!(
+        outcome.reason,
+        Some(EndReason::Road {
+            squares: game.board.road(Colour::Black).unwrap()
+        })
+    );
+    assert_eq!(outcome.result_ptn(), Some("0-R"));
+
+    let game = Game::<3>::from_ptn("1. a3 c1 2. c2 c3 3. b3 b2 4. b1 a1 5. a2")?;
+    let outcome = game.outcome();
+    assert_eq!(outcome.reason, Some(EndReason::FlatsBoardFull));
+    assert_eq!((outcome.white_flats, outcome.black_flats), (5, 4));
+    assert_eq!(outcome.result_ptn(), Some("F-0"));
+
+    let mut game = Game::<3>::with_rules(Rules {
+        stones: 2,
+        caps: 1,
+        komi: Komi::from_half_points(1),
+        ..Rules::standard(3).unwrap()
+    })?;
+    game.play_ptn_moves(&["a1", "c3", "Cb2", "a3", "Sc1"])?;
+    let outcome = game.outcome();
+    assert_eq!(outcome.reason, Some(EndReason::FlatsReservesExhausted));
+    assert_eq!((outcome.white_flats, outcome.black_flats), (1, 2));
+    assert_eq!(outcome.komi, Komi::from_half_points(1));
+    assert_eq!(outcome.result_ptn(), Some("0-F"));
+
+    let mut game = Game::<5>::with_rules(Rules {
+        turn_limit: Some(2),
+        ..Rules::standard(5).unwrap()
+    })?;
+    assert_eq!(game.outcome().reason, None);
+    assert_eq!(game.outcome().result_ptn(), None);
+    game.play_ptn_moves(&["a1", "e5"])?;
+    assert_eq!(game.outcome().reason, Some(EndReason::TurnLimit));
+    assert_eq!(game.outcome().result_ptn(), Some("1/2-1/2"));
+    Ok(())
+}
@@ -0,0 +1,107 @@
+use std::collections::HashMap;
+
+use tak::*;
+
+#[test]
+fn incremental_matches_scratch() -> TakResult<()> {
+    let mut game = Game::<5>::with_komi(2);
+    assert_eq!(game.hash(), game.compute_hash());
+    while game.winner() == GameResult::Ongoing {
+        let before = game.hash();
+        for turn in game.possible_turns() {
+            let undo = game.play_undoable(turn)?;
+            assert_eq!(game.hash(), game.compute_hash());
+            assert_ne!(game.hash(), before);
+            game.undo(undo);
+            assert_eq!(game.hash(), before);
+        }
+        game.nth_move(9_576_890_767)?;
+    }
+    Ok(())
+}
+
+#[test]
+fn transpositions_share_key() -> TakResult<()> {
+    let a = Game::<5>::from_ptn("1. a1 e1 2. c3 d3 3. b2 d4")?;
+    let b = Game::<5>::from_ptn("1. a1 e1 2. b2 d4 3. c3 d3")?;
+    assert_eq!(a.hash(), b.hash());
+
+    // same board, different player to move
+    let c = Game::<5>::from_ptn("[TPS \"x5/x3,2,x/x2,1,2,x/x,1,x3/2,x3,1 2 4\"]")?;
+    assert_eq!(a.board.hash(), c.board.hash());
+    assert_ne!(a.hash(), c.hash());
+
+    // same board, different komi
+    let d = Game {
+        rules: Rules {
+            komi: Komi::from(2),
+            ..a.rules
+        },
+        ..a.clone()
+    };
+    assert_ne!(a.hash(), d.hash());
+    Ok(())
+}
+
+#[test]
+fn stack_contents_change_key() -> TakResult<()> {
+    let a = Board::<5>::from_tps("x5/x5/x5/x5/1212,x4")?;
+    let b = Board::<5>::from_tps("x5/x5/x5/x5/2112,x4")?;
+    let c = Board::<5>::from_tps("x5/x5/x5/x5/212,x4")?;
+    assert_ne!(a.hash(), b.hash());
+    assert_ne!(a.hash(), c.hash());
+    assert_ne!(b.hash(), c.hash());
+
+    // stacks which only differ below the hashed depth collide
+    let deep = Board::<5>::from_tps("x5/x5/x5/x5/121212121212,x4")?;
+    let deeper = Board::<5>::from_tps("x5/x5/x5/x5/221212121212,x4")?;
+    assert_eq!(deep.hash(), deeper.hash());
+    assert_ne!(deep, deeper);
+    Ok(())
+}
+
+#[test]
+fn configurable_stack_depth() -> TakResult<()> {
+    // the stacks only differ 11 and 12 pieces under the top
+    let deep = Game::<5>::from_tps("x5/x5/x5/x5/1221212121212,x4 1 10 (15/1) (14/1) 0")?;
+    let deeper = Game::<5>::from_tps("x5/x5/x5/x5/2121212121212,x4 1 10 (15/1) (14/1) 0")?;
+    assert_eq!(Zobrist::default().game(&deep), deep.hash());
+    assert_eq!(Zobrist::default(), Zobrist::new(ZOBRIST_STACK_DEPTH));
+
+    let shallow = Zobrist::new(10);
+    assert_eq!(shallow.board(&deep.board), shallow.board(&deeper.board));
+    assert_eq!(shallow.game(&deep), shallow.game(&deeper));
+    for depth in [11, 12, Stack::CAPACITY] {
+        let zobrist = Zobrist::new(depth);
+        let (a, b) = (zobrist.board(&deep.board), zobrist.board(&deeper.board));
+        assert_ne!(a, b, "{depth}");
+        assert_ne!(zobrist.game(&deep), zobrist.game(&deeper), "{depth}");
+    }
+    Ok(())
+}
+
+#[test]
+fn few_collisions() -> TakResult<()> {
+    let mut seen = HashMap::new();
+    for seed in [1_000_000_007, 998_244_353, 9_576_890_767] {
+        let mut game = Game::<4>::default();
+        while game.winner() == GameResult::Ongoing {
+            // everything the key covers
+            let position = format!(
+                "{} {} {} {} {} {}",
+                game.board.to_tps(),
+                game.to_move,
+                game.white_stones,
+                game.white_caps,
+                game.black_stones,
+                game.black_caps
+            );
+            assert_eq!(
+                seen.entry(game.hash()).or_insert_with(|| position.clone()),
+                &position
+            );
+            game.nth_move(seed)?;
+        }
+    }
+    Ok(())
+}
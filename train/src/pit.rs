@@ -24,6 +24,11 @@ pub struct PitResult {
     wins: u32,
     draws: u32,
     losses: u32,
+    /// How the games ended, whoever won.
+    roads: u32,
+    full_boards: u32,
+    reserves_exhausted: u32,
+    turn_limits: u32,
 }
 
 impl PitResult {
@@ -34,8 +39,15 @@ impl PitResult {
         self.wins as f64 / (self.wins + self.losses) as f64
     }
 
-    fn update(&mut self, result: GameResult, colour: Colour) {
-        match result {
+    fn update(&mut self, outcome: Outcome<N>, colour: Colour) {
+        match outcome.reason {
+            Some(EndReason::Road { .. }) => self.roads += 1,
+            Some(EndReason::FlatsBoardFull) => self.full_boards += 1,
+            Some(EndReason::FlatsReservesExhausted) => self.reserves_exhausted += 1,
+            Some(EndReason::TurnLimit) => self.turn_limits += 1,
+            None => {}
+        }
+        match outcome.result {
             GameResult::Winner { colour: winner, .. } => {
                 if winner == colour {
                     self.wins += 1
@@ -82,9 +94,8 @@ fn pit_game<A: Agent<N>>(
     new: &A,
     old: &A,
     _index: usize,
-) -> (GameResult, GameResult, Vec<Example<N>>, ArrayVec<Analysis<N>, 4>)
+) -> (Outcome<N>, Outcome<N>, Vec<Example<N>>, ArrayVec<Analysis<N>, 4>)
 where
-    [[Option<Tile>; N]; N]: Default,
     Turn<N>: Lut,
 {
     let mut results = ArrayVec::<_, 2>::new();
@@ -93,7 +104,8 @@ where
 
     // Play one game as white and one game as black from the same opening.
     for my_colour in [Colour::White, Colour::Black] {
-        let mut game = Game::with_komi(KOMI);
+        let start = Game::with_komi(KOMI);
+        let mut game = start.clone();
 
         // TODO proper opening book using index
         let opening = vec![
@@ -113,8 +125,8 @@ where
             game.play(turn).unwrap()
         }
 
-        let mut new_player = Player::new(new, opening.clone(), game.komi);
-        let mut old_player = Player::new(old, opening, game.komi);
+        let mut new_player = Player::new(new, opening.clone(), start.clone());
+        let mut old_player = Player::new(old, opening, start);
 
         const PIT_NOISE_PLIES: u64 = 20;
 
@@ -138,8 +150,9 @@ where
             game.play(turn).unwrap();
         }
 
-        let winner = game.winner();
-        results.push(winner);
+        let outcome = game.outcome();
+        let winner = outcome.result;
+        results.push(outcome);
 
         examples.extend(
             new_player
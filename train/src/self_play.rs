@@ -51,7 +51,8 @@ pub fn self_play(network: &Network<N>) -> Vec<Example<N>> {
 }
 
 fn self_play_game<A: Agent<N>>(agent: &A, _index: usize) -> (Vec<Example<N>>, Analysis<N>) {
-    let mut game = Game::with_komi(KOMI);
+    let start = Game::with_komi(KOMI);
+    let mut game = start.clone();
 
     // TODO proper opening book using index
     let opening = vec![
@@ -71,7 +72,7 @@ fn self_play_game<A: Agent<N>>(agent: &A, _index: usize) -> (Vec<Example<N>>, An
         game.play(turn).unwrap()
     }
 
-    let mut player = Player::new(agent, opening, game.komi);
+    let mut player = Player::new(agent, opening, start);
 
     while matches!(game.winner(), GameResult::Ongoing) {
         if game.ply < NOISE_PLIES {